@@ -1,7 +1,5 @@
 extern crate core;
 
-mod simple_ex;
-
 use std::marker::PhantomData;
 
 use halo2_proofs::arithmetic::Field;
@@ -14,6 +12,8 @@ use halo2_proofs::poly::Rotation;
 #[derive(Debug, Clone)]
 struct ACell<F: Field>(AssignedCell<F, F>);
 
+type FirstRow<F> = (ACell<F>, ACell<F>, ACell<F>);
+
 
 #[derive(Debug, Clone)]
 struct FiboConfig {
@@ -57,7 +57,7 @@ impl<F: Field> FiboChip<F> {
     }
 
     fn assign_first_row(&self, mut layouter: impl Layouter<F>, a: Value<F>, b: Value<F>)
-                        -> Result<(ACell<F>, ACell<F>, ACell<F>), Error> {
+                        -> Result<FirstRow<F>, Error> {
         layouter.assign_region(
             || "first row",
             |mut region| {
@@ -1,153 +1,10 @@
-use std::marker::PhantomData;
-
 use halo2_proofs::arithmetic::Field;
-use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, SimpleFloorPlanner, Value};
+use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner, Value};
 use halo2_proofs::dev::MockProver;
 use halo2_proofs::pasta::Fp;
-use halo2_proofs::plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Fixed, Instance, Selector};
-use halo2_proofs::poly::Rotation;
-
-trait NumericInstructions<F: Field>: Chip<F> {
-    type Num;
-
-    fn load_private(&self, layouter: impl Layouter<F>, a: Value<F>) -> Result<Self::Num, Error>;
-
-    fn load_constant(&self, layouter: impl Layouter<F>, a: F) -> Result<Self::Num, Error>;
-
-    fn mul(&self, layouter: impl Layouter<F>, a: Self::Num, b: Self::Num) -> Result<Self::Num, Error>;
-
-    fn expose_public(
-        &self,
-        layouter: impl Layouter<F>,
-        num: Self::Num,
-        row: usize,
-    ) -> Result<(), Error>;
-}
-
-#[derive(Clone, Debug)]
-struct FieldConfig {
-    advice: [Column<Advice>; 2],
-
-    instance: Column<Instance>,
-
-    s_mul: Selector,
-}
-
-struct FieldChip<F: Field> {
-    config: FieldConfig,
-    _marker: PhantomData<F>,
-}
-
-impl<F: Field> Chip<F> for FieldChip<F> {
-    type Config = FieldConfig;
-    type Loaded = ();
-
-    fn config(&self) -> &Self::Config {
-        &self.config
-    }
-
-    fn loaded(&self) -> &Self::Loaded {
-        &()
-    }
-}
-
-impl<F: Field> FieldChip<F> {
-    fn construct(config: <Self as Chip<F>>::Config) -> Self {
-        Self {
-            config,
-            _marker: PhantomData,
-        }
-    }
-
-    fn configure(
-        meta: &mut ConstraintSystem<F>,
-        advice: [Column<Advice>; 2],
-        instance: Column<Instance>,
-        constant: Column<Fixed>,
-    ) -> <Self as Chip<F>>::Config {
-        meta.enable_constant(constant);
-        meta.enable_equality(instance);
-        for column in advice {
-            meta.enable_equality(column);
-        }
-        let s_mul = meta.selector();
-
-        meta.create_gate("mul", |meta| {
-            let lhs = meta.query_advice(advice[0], Rotation::cur());
-            let rhs = meta.query_advice(advice[1], Rotation::cur());
-            let out = meta.query_advice(advice[0], Rotation::next());
-            let s_mul = meta.query_selector(s_mul);
-
-
-            vec![s_mul * (lhs * rhs - out)]
-        });
-
-        FieldConfig {
-            advice,
-            instance,
-            s_mul,
-        }
-    }
-}
-
-#[derive(Clone)]
-struct Number<F: Field>(AssignedCell<F, F>);
-
-impl<F: Field> NumericInstructions<F> for FieldChip<F> {
-    type Num = Number<F>;
-
-    fn load_private(&self, mut layouter: impl Layouter<F>, a: Value<F>) -> Result<Self::Num, Error> {
-        let config = self.config();
-
-        layouter.assign_region(
-            || "load private",
-            |mut region| {
-                region
-                    .assign_advice(|| "private input", config.advice[0], 0, || a)
-                    .map(Number)
-            },
-        )
-    }
-
-    fn load_constant(&self, mut layouter: impl Layouter<F>, a: F) -> Result<Self::Num, Error> {
-        let config = self.config();
-
-        layouter.assign_region(
-            || "load constant",
-            |mut region| {
-                region
-                    .assign_advice_from_constant(|| "constant value", config.advice[0], 0, a)
-                    .map(Number)
-            },
-        )
-    }
-
-    fn mul(&self, mut layouter: impl Layouter<F>, a: Self::Num, b: Self::Num) -> Result<Self::Num, Error> {
-        let config = self.config();
-
-        layouter.assign_region(
-            || "mut",
-            |mut region| {
-                config.s_mul.enable(&mut region, 0)?;
-
-                a.0.copy_advice(|| "lhs", &mut region, self.config.advice[0], 0)?;
-                b.0.copy_advice(|| "rhs", &mut region, self.config.advice[1], 0)?;
+use halo2_proofs::plonk::{Circuit, Column, ConstraintSystem, Error, Instance};
 
-                let value = a.0.value().copied() * b.0.value();
-
-                region
-                    .assign_advice(|| "lhs * rhs", self.config.advice[0], 1, || value)
-                    .map(Number)
-            }
-        )
-    }
-
-    fn expose_public(&self, mut layouter: impl Layouter<F>, num: Self::Num, row: usize) -> Result<(), Error> {
-        let config = self.config();
-
-        layouter.constrain_instance(num.0.cell(), config.instance, row)
-    }
-}
+use halo2_examples::gadgets::arithmetic::{ArithmeticChip, ArithmeticConfig};
 
 #[derive(Default)]
 struct MyCircuit<F: Field> {
@@ -157,7 +14,7 @@ struct MyCircuit<F: Field> {
 }
 
 impl<F: Field> Circuit<F> for MyCircuit<F> {
-    type Config = FieldConfig;
+    type Config = (ArithmeticConfig, Column<Instance>);
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
@@ -165,27 +22,32 @@ impl<F: Field> Circuit<F> for MyCircuit<F> {
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let advice = [meta.advice_column(), meta.advice_column()];
+        let advice = [meta.advice_column(), meta.advice_column(), meta.advice_column()];
 
         let instance = meta.instance_column();
 
+        meta.enable_equality(instance);
+
         let constant = meta.fixed_column();
 
-        FieldChip::configure(meta, advice, instance, constant)
+        (ArithmeticChip::configure(meta, advice, constant), instance)
     }
 
     fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
-        let chip = FieldChip::construct(config);
+        let (config, instance) = config;
+
+        let chip = ArithmeticChip::construct(config);
 
         let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
         let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
 
         let constant = chip.load_constant(layouter.namespace(|| "load constant"), self.constant)?;
 
-        let ab = chip.mul(layouter.namespace(|| "a * b"), a, b)?;
-        let abab = chip.mul(layouter.namespace(|| "ab * ab"), ab.clone(), ab)?;
+        let ab = chip.mul(layouter.namespace(|| "a * b"), &a, &b)?;
+        let absq = chip.square(layouter.namespace(|| "ab * ab"), &ab)?;
+        let c = chip.mul(layouter.namespace(|| "constant * absq"), &constant, &absq)?;
 
-        chip.expose_public(layouter.namespace(|| "expose absq"), abab, 0)
+        layouter.constrain_instance(c.cell(), instance, 0)
     }
 }
 
@@ -194,11 +56,12 @@ fn main() {
 
     let a = Fp::from(2);
     let b = Fp::from(3);
+    let constant = Fp::from(7);
 
-    let c = a.square() * b.square();
+    let c = constant * a.square() * b.square();
 
     let circuit = MyCircuit {
-        constant: Fp::from(7),
+        constant,
         a: Value::known(a),
         b: Value::known(b),
     };
@@ -210,6 +73,5 @@ fn main() {
 
     public_inputs[0] += Fp::one();
     let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
-    prover.assert_satisfied();
-
+    assert!(prover.verify().is_err());
 }
@@ -1,2 +1,12 @@
+pub mod arithmetic;
+pub mod bitwise;
+pub mod boolean;
+pub mod inverse;
+pub mod is_equal;
 pub mod is_zero;
-pub mod range_check_with_bits;
\ No newline at end of file
+pub mod plonk;
+pub mod pow;
+pub mod range_check_with_bits;
+pub mod select;
+pub mod uint;
+pub mod vector_zero;
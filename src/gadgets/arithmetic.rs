@@ -0,0 +1,401 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+/// Basic field arithmetic on assigned cells, one row per operation:
+///
+/// ```text
+///  |  lhs  |  rhs  |  out  |  coeff  | s_add | s_sub | s_mul | s_add_const | s_mul_const |
+///  ---------------------------------------------------------------------------------------
+///  |   a   |   b   |  out  |    c    |   .   |   .   |   .   |      .      |      .      |
+/// ```
+///
+/// ```text
+///  s_add:        out = a + b
+///  s_sub:        out = a - b
+///  s_mul:        out = a * b
+///  s_add_const:  out = a + c
+///  s_mul_const:  out = a * c
+/// ```
+///
+/// `neg`, `square` and `sum` are built from these gates.
+#[derive(Clone, Debug)]
+pub struct ArithmeticConfig {
+    advice: [Column<Advice>; 3],
+    coeff: Column<Fixed>,
+    s_add: Selector,
+    s_sub: Selector,
+    s_mul: Selector,
+    s_add_const: Selector,
+    s_mul_const: Selector,
+}
+
+pub struct ArithmeticChip<F: Field> {
+    config: ArithmeticConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Chip<F> for ArithmeticChip<F> {
+    type Config = ArithmeticConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> ArithmeticChip<F> {
+    pub fn construct(config: ArithmeticConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chip on `[lhs, rhs, out]`, with `constant` enabled for loading
+    /// constants.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        constant: Column<Fixed>,
+    ) -> ArithmeticConfig {
+        meta.enable_constant(constant);
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let coeff = meta.fixed_column();
+
+        let s_add = meta.selector();
+        let s_sub = meta.selector();
+        let s_mul = meta.selector();
+        let s_add_const = meta.selector();
+        let s_mul_const = meta.selector();
+
+        meta.create_gate("add", |meta| {
+            let s_add = meta.query_selector(s_add);
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let rhs = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[2], Rotation::cur());
+
+            Constraints::with_selector(s_add, [lhs + rhs - out])
+        });
+
+        meta.create_gate("sub", |meta| {
+            let s_sub = meta.query_selector(s_sub);
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let rhs = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[2], Rotation::cur());
+
+            Constraints::with_selector(s_sub, [lhs - rhs - out])
+        });
+
+        meta.create_gate("mul", |meta| {
+            let s_mul = meta.query_selector(s_mul);
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let rhs = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[2], Rotation::cur());
+
+            Constraints::with_selector(s_mul, [lhs * rhs - out])
+        });
+
+        meta.create_gate("add const", |meta| {
+            let s_add_const = meta.query_selector(s_add_const);
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let coeff = meta.query_fixed(coeff);
+            let out = meta.query_advice(advice[2], Rotation::cur());
+
+            Constraints::with_selector(s_add_const, [lhs + coeff - out])
+        });
+
+        meta.create_gate("mul const", |meta| {
+            let s_mul_const = meta.query_selector(s_mul_const);
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let coeff = meta.query_fixed(coeff);
+            let out = meta.query_advice(advice[2], Rotation::cur());
+
+            Constraints::with_selector(s_mul_const, [lhs * coeff - out])
+        });
+
+        ArithmeticConfig {
+            advice,
+            coeff,
+            s_add,
+            s_sub,
+            s_mul,
+            s_add_const,
+            s_mul_const,
+        }
+    }
+
+    pub fn load_private(&self, mut layouter: impl Layouter<F>, value: Value<F>) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", config.advice[0], 0, || value),
+        )
+    }
+
+    pub fn load_constant(&self, mut layouter: impl Layouter<F>, constant: F) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load constant",
+            |mut region| region.assign_advice_from_constant(|| "constant value", config.advice[0], 0, constant),
+        )
+    }
+
+    fn binary_op(
+        &self,
+        mut layouter: impl Layouter<F>,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        op: impl Fn(F, F) -> F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "binary op",
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
+
+                let out = a.value().zip(b.value()).map(|(a, b)| op(*a, *b));
+
+                region.assign_advice(|| "out", config.advice[2], 0, || out)
+            },
+        )
+    }
+
+    fn constant_op(
+        &self,
+        mut layouter: impl Layouter<F>,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        constant: F,
+        op: impl Fn(F, F) -> F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "constant op",
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
+                region.assign_fixed(|| "coeff", config.coeff, 0, || Value::known(constant))?;
+
+                let out = a.value().map(|a| op(*a, constant));
+
+                region.assign_advice(|| "out", config.advice[2], 0, || out)
+            },
+        )
+    }
+
+    pub fn add(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.binary_op(layouter, self.config.s_add, a, b, |a, b| a + b)
+    }
+
+    pub fn sub(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.binary_op(layouter, self.config.s_sub, a, b, |a, b| a - b)
+    }
+
+    pub fn mul(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.binary_op(layouter, self.config.s_mul, a, b, |a, b| a * b)
+    }
+
+    pub fn add_const(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, constant: F) -> Result<AssignedCell<F, F>, Error> {
+        self.constant_op(layouter, self.config.s_add_const, a, constant, |a, c| a + c)
+    }
+
+    pub fn mul_const(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, constant: F) -> Result<AssignedCell<F, F>, Error> {
+        self.constant_op(layouter, self.config.s_mul_const, a, constant, |a, c| a * c)
+    }
+
+    pub fn neg(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.mul_const(layouter, a, -F::ONE)
+    }
+
+    pub fn square(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.mul(layouter, a, a)
+    }
+
+    /// Returns the sum of `values`, or a constant zero for an empty slice.
+    pub fn sum(&self, mut layouter: impl Layouter<F>, values: &[AssignedCell<F, F>]) -> Result<AssignedCell<F, F>, Error> {
+        let Some((first, rest)) = values.split_first() else {
+            return self.load_constant(layouter.namespace(|| "zero"), F::ZERO);
+        };
+
+        rest.iter().enumerate().try_fold(first.clone(), |acc, (i, value)| {
+            self.add(layouter.namespace(|| format!("sum {}", i)), &acc, value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    /// The operation applied to the loaded values.
+    #[derive(Clone, Copy)]
+    enum Op<F> {
+        Add,
+        Sub,
+        Mul,
+        AddConst(F),
+        MulConst(F),
+        Neg,
+        Square,
+        Sum,
+        /// A `mul` row laid out by hand with the given `out`, so that only the gate can
+        /// reject it.
+        ForgedMul(F),
+    }
+
+    struct ArithmeticCircuit<F: Field> {
+        op: Op<F>,
+        values: Vec<Value<F>>,
+    }
+
+    impl<F: Field> Circuit<F> for ArithmeticCircuit<F> {
+        type Config = (ArithmeticConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                op: self.op,
+                values: vec![Value::unknown(); self.values.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let constant = meta.fixed_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            (ArithmeticChip::configure(meta, advice, constant), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            let chip = ArithmeticChip::construct(config);
+
+            let values = self.values
+                .iter()
+                .map(|value| chip.load_private(layouter.namespace(|| "value"), *value))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let out = match self.op {
+                Op::Add => chip.add(layouter.namespace(|| "add"), &values[0], &values[1]),
+                Op::Sub => chip.sub(layouter.namespace(|| "sub"), &values[0], &values[1]),
+                Op::Mul => chip.mul(layouter.namespace(|| "mul"), &values[0], &values[1]),
+                Op::AddConst(constant) => chip.add_const(layouter.namespace(|| "add const"), &values[0], constant),
+                Op::MulConst(constant) => chip.mul_const(layouter.namespace(|| "mul const"), &values[0], constant),
+                Op::Neg => chip.neg(layouter.namespace(|| "neg"), &values[0]),
+                Op::Square => chip.square(layouter.namespace(|| "square"), &values[0]),
+                Op::Sum => chip.sum(layouter.namespace(|| "sum"), &values),
+                Op::ForgedMul(out) => {
+                    let config = chip.config();
+
+                    layouter.assign_region(
+                        || "forged mul",
+                        |mut region| {
+                            config.s_mul.enable(&mut region, 0)?;
+
+                            values[0].copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
+                            values[1].copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
+                            region.assign_advice(|| "out", config.advice[2], 0, || Value::known(out))
+                        },
+                    )
+                }
+            }?;
+
+            layouter.constrain_instance(out.cell(), instance, 0)
+        }
+    }
+
+    fn run(op: Op<Fp>, values: &[u64], expected: Fp) -> bool {
+        let circuit = ArithmeticCircuit {
+            op,
+            values: values.iter().map(|&value| Value::known(Fp::from(value))).collect(),
+        };
+
+        MockProver::run(4, &circuit, vec![vec![expected]]).unwrap().verify().is_ok()
+    }
+
+    #[test]
+    fn test_add() {
+        assert!(run(Op::Add, &[7, 3], Fp::from(10)));
+        assert!(!run(Op::Add, &[7, 3], Fp::from(11)));
+    }
+
+    #[test]
+    fn test_sub() {
+        assert!(run(Op::Sub, &[3, 7], -Fp::from(4)));
+        assert!(!run(Op::Sub, &[3, 7], Fp::from(4)));
+    }
+
+    #[test]
+    fn test_mul() {
+        assert!(run(Op::Mul, &[7, 3], Fp::from(21)));
+        assert!(!run(Op::Mul, &[7, 3], Fp::from(10)));
+    }
+
+    #[test]
+    fn test_forged_mul_rejected() {
+        assert!(!run(Op::ForgedMul(Fp::from(22)), &[7, 3], Fp::from(22)));
+        assert!(run(Op::ForgedMul(Fp::from(21)), &[7, 3], Fp::from(21)));
+    }
+
+    #[test]
+    fn test_add_const() {
+        assert!(run(Op::AddConst(Fp::from(5)), &[7], Fp::from(12)));
+        assert!(!run(Op::AddConst(Fp::from(5)), &[7], Fp::from(7)));
+    }
+
+    #[test]
+    fn test_mul_const() {
+        assert!(run(Op::MulConst(Fp::from(5)), &[7], Fp::from(35)));
+        assert!(!run(Op::MulConst(Fp::from(5)), &[7], Fp::from(12)));
+    }
+
+    #[test]
+    fn test_neg() {
+        assert!(run(Op::Neg, &[7], -Fp::from(7)));
+        assert!(!run(Op::Neg, &[7], Fp::from(7)));
+    }
+
+    #[test]
+    fn test_square() {
+        assert!(run(Op::Square, &[7], Fp::from(49)));
+        assert!(!run(Op::Square, &[7], Fp::from(14)));
+    }
+
+    #[test]
+    fn test_sum() {
+        assert!(run(Op::Sum, &[7, 3, 5], Fp::from(15)));
+        assert!(!run(Op::Sum, &[7, 3, 5], Fp::from(10)));
+
+        assert!(run(Op::Sum, &[], Fp::ZERO));
+    }
+}
@@ -0,0 +1,449 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+pub use crate::gadgets::bitwise::table::BitwiseTable;
+use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+
+mod table;
+
+/// A bitwise operation supported by [`BitwiseTable`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    And,
+    Or,
+    Xor,
+}
+
+impl BitwiseOp {
+    /// The tag of the operation in the table. `And` is tagged with 0 so that disabled
+    /// lookups, which query all zeros, hit the row `0 & 0 = 0`.
+    fn tag<F: PrimeField>(&self) -> F {
+        F::from(*self as u64)
+    }
+
+    fn apply(&self, a: u64, b: u64) -> u64 {
+        match self {
+            BitwiseOp::And => a & b,
+            BitwiseOp::Or => a | b,
+            BitwiseOp::Xor => a ^ b,
+        }
+    }
+}
+
+/// This gadget applies bitwise operations to N-bit words, one K-bit chunk at a time.
+///
+/// Both operands are decomposed into K-bit chunks with the running sum of
+/// [`DecomposeChip`], and each pair of chunks is looked up in the table together with
+/// the result `r_i = a_i op b_i`. The results are recombined with a running sum in the
+/// other direction:
+///
+/// ```text
+///  z_C = 0,   z_i = r_i + 2^K * z_{i+1},   result = z_0
+/// ```
+///
+/// ```text
+///  |   a   |   b   |  out  |  z  |  op  | q_lookup | q_recompose |
+///  ---------------------------------------------------------------
+///  |  a_0  |  b_0  |  r_0  | z_0 |  op  |    1     |      1      |
+///  |  ...  |  ...  |  ...  | ... | ...  |   ...    |     ...     |
+///  |a_{C-1}|b_{C-1}|r_{C-1}|     |  op  |    1     |      1      |
+///  |       |       |       | z_C |      |    0     |      0      |
+/// ```
+///
+/// NOT is computed as XOR with the all-ones word. With K = 8 the table has 3 * 2^16
+/// rows, so the circuit needs k >= 18.
+#[derive(Clone, Debug)]
+pub struct BitwiseConfig<F: PrimeField> {
+    advice: [Column<Advice>; 4],
+    op: Column<Fixed>,
+    lookup_selector: Selector,
+    recompose_selector: Selector,
+    table: BitwiseTable<F>,
+    decompose: DecomposeConfig<F>,
+}
+
+impl<F: PrimeField> BitwiseConfig<F> {
+    pub fn table(&self) -> &BitwiseTable<F> {
+        &self.table
+    }
+
+    pub fn decompose(&self) -> &DecomposeConfig<F> {
+        &self.decompose
+    }
+}
+
+pub struct BitwiseChip<F: PrimeField> {
+    config: BitwiseConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for BitwiseChip<F> {
+    type Config = BitwiseConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> BitwiseChip<F> {
+    pub fn construct(config: BitwiseConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chip; `table` and `decompose` must use the same chunk size K.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        table: BitwiseTable<F>,
+        decompose: DecomposeConfig<F>,
+    ) -> BitwiseConfig<F> {
+        assert_eq!(table.lookup_bits(), decompose.lookup_bits());
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let [a, b, out, z] = advice;
+
+        let op = meta.fixed_column();
+
+        let lookup_selector = meta.complex_selector();
+        let recompose_selector = meta.selector();
+
+        meta.lookup(|meta| {
+            let lookup_selector = meta.query_selector(lookup_selector);
+            let op = meta.query_fixed(op);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            vec![
+                (lookup_selector.clone() * op, table.op),
+                (lookup_selector.clone() * a, table.a),
+                (lookup_selector.clone() * b, table.b),
+                (lookup_selector * out, table.out),
+            ]
+        });
+
+        let two_to_k = Expression::Constant(F::from(1 << table.lookup_bits()));
+
+        meta.create_gate("recompose", |meta| {
+            let recompose_selector = meta.query_selector(recompose_selector);
+            let out = meta.query_advice(out, Rotation::cur());
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+
+            Constraints::with_selector(recompose_selector, [("z_i = r_i + 2^K * z_{i+1}", out + z_next * two_to_k - z_cur)])
+        });
+
+        BitwiseConfig {
+            advice,
+            op,
+            lookup_selector,
+            recompose_selector,
+            table,
+            decompose,
+        }
+    }
+
+    /// Returns `a op b` for `num_bits`-bit words `a` and `b`, constraining both operands to
+    /// `num_bits` bits. `num_bits` must be a multiple of K.
+    pub fn apply(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: BitwiseOp,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let lookup_bits = config.table.lookup_bits();
+
+        assert!(num_bits > 0 && num_bits.is_multiple_of(lookup_bits), "word size must be a multiple of K");
+        assert!(num_bits < F::NUM_BITS as usize);
+
+        let decompose_chip = DecomposeChip::construct(config.decompose.clone());
+
+        let a = decompose_chip.assign(layouter.namespace(|| "decompose a"), a, num_bits, true)?;
+        let b = decompose_chip.assign(layouter.namespace(|| "decompose b"), b, num_bits, true)?;
+
+        let two_to_k = F::from(1 << lookup_bits);
+
+        layouter.assign_region(
+            || "bitwise",
+            |mut region| {
+                let [a_column, b_column, out_column, z_column] = config.advice;
+
+                let num_chunks = a.chunks.len();
+
+                let mut outs = vec![];
+
+                for (i, (a_i, b_i)) in a.chunks.iter().zip(b.chunks.iter()).enumerate() {
+                    config.lookup_selector.enable(&mut region, i)?;
+                    config.recompose_selector.enable(&mut region, i)?;
+
+                    region.assign_fixed(|| "op", config.op, i, || Value::known(op.tag::<F>()))?;
+
+                    a_i.copy_advice(|| "a_i", &mut region, a_column, i)?;
+                    b_i.copy_advice(|| "b_i", &mut region, b_column, i)?;
+
+                    let out = a_i.value().zip(b_i.value()).map(|(a_i, b_i)| {
+                        let to_u64 = |value: &F| DecomposeChip::chunks(*value, 1, lookup_bits)[0].0;
+
+                        F::from(op.apply(to_u64(a_i), to_u64(b_i)))
+                    });
+
+                    outs.push(region.assign_advice(|| "r_i", out_column, i, || out)?);
+                }
+
+                let mut z = region.assign_advice_from_constant(|| "z_C", z_column, num_chunks, F::ZERO)?;
+
+                for (i, out) in outs.iter().enumerate().rev() {
+                    let z_i = z.value().copied().zip(out.value()).map(|(z, out)| *out + z * two_to_k);
+
+                    z = region.assign_advice(|| "z_i", z_column, i, || z_i)?;
+                }
+
+                Ok(z)
+            },
+        )
+    }
+
+    pub fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.apply(layouter, BitwiseOp::And, a, b, num_bits)
+    }
+
+    pub fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.apply(layouter, BitwiseOp::Or, a, b, num_bits)
+    }
+
+    pub fn xor(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.apply(layouter, BitwiseOp::Xor, a, b, num_bits)
+    }
+
+    /// Returns the bitwise complement of the `num_bits`-bit word `a`.
+    pub fn not(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let ones = F::from(2).pow_vartime([num_bits as u64]) - F::ONE;
+
+        let ones = layouter.assign_region(
+            || "all ones",
+            |mut region| region.assign_advice_from_constant(|| "2^N - 1", config.advice[1], 0, ones),
+        )?;
+
+        self.xor(layouter.namespace(|| "xor all ones"), a, ones, num_bits)
+    }
+
+    pub fn and_u8(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>, b: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.and(layouter, a, b, 8)
+    }
+
+    pub fn and_u32(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>, b: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.and(layouter, a, b, 32)
+    }
+
+    pub fn and_u64(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>, b: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.and(layouter, a, b, 64)
+    }
+
+    pub fn or_u8(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>, b: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.or(layouter, a, b, 8)
+    }
+
+    pub fn or_u32(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>, b: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.or(layouter, a, b, 32)
+    }
+
+    pub fn or_u64(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>, b: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.or(layouter, a, b, 64)
+    }
+
+    pub fn xor_u8(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>, b: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.xor(layouter, a, b, 8)
+    }
+
+    pub fn xor_u32(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>, b: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.xor(layouter, a, b, 32)
+    }
+
+    pub fn xor_u64(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>, b: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.xor(layouter, a, b, 64)
+    }
+
+    pub fn not_u8(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.not(layouter, a, 8)
+    }
+
+    pub fn not_u32(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.not(layouter, a, 32)
+    }
+
+    pub fn not_u64(&self, layouter: impl Layouter<F>, a: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.not(layouter, a, 64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+    use super::*;
+
+    struct BitwiseCircuit<F: PrimeField, const LOOKUP_BITS: usize> {
+        a: Value<F>,
+        b: Value<F>,
+        num_bits: usize,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits, const LOOKUP_BITS: usize> Circuit<F> for BitwiseCircuit<F, LOOKUP_BITS> {
+        type Config = (BitwiseConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: Value::unknown(),
+                b: Value::unknown(),
+                num_bits: self.num_bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let c_i_bits = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            let range_table = RangeCheckTable::configure(meta, 1 << LOOKUP_BITS);
+            let decompose = DecomposeChip::configure(meta, advice[0], advice[1], c_i_bits, range_table, LOOKUP_BITS);
+
+            let table = BitwiseTable::configure(meta, LOOKUP_BITS);
+
+            (BitwiseChip::configure(meta, advice, table, decompose), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            config.table().load(layouter.namespace(|| "bitwise table"))?;
+            config.decompose().lookup_table().load(layouter.namespace(|| "range table"))?;
+
+            let (a, b) = layouter.assign_region(|| "assign values", |mut region| {
+                let a = region.assign_advice(|| "a", config.advice[0], 0, || self.a)?;
+                let b = region.assign_advice(|| "b", config.advice[1], 0, || self.b)?;
+
+                Ok((a, b))
+            })?;
+
+            let chip = BitwiseChip::construct(config);
+
+            let outputs = [
+                chip.and(layouter.namespace(|| "and"), a.clone(), b.clone(), self.num_bits)?,
+                chip.or(layouter.namespace(|| "or"), a.clone(), b.clone(), self.num_bits)?,
+                chip.xor(layouter.namespace(|| "xor"), a.clone(), b, self.num_bits)?,
+                chip.not(layouter.namespace(|| "not"), a, self.num_bits)?,
+            ];
+
+            for (row, output) in outputs.iter().enumerate() {
+                layouter.constrain_instance(output.cell(), instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn public_inputs(a: u64, b: u64, num_bits: usize) -> Vec<Vec<Fp>> {
+        let mask = u64::MAX >> (64 - num_bits);
+
+        vec![[a & b, a | b, a ^ b, !a & mask].iter().map(|&value| Fp::from(value)).collect()]
+    }
+
+    #[test]
+    fn test_bitwise_words() {
+        for (a, b, num_bits) in [(0b1100, 0b1010, 4), (0xdead_beef, 0x0123_4567, 32), (u64::MAX, 0x0f0f_0f0f_0f0f_0f0f, 64)] {
+            let circuit = BitwiseCircuit::<Fp, 4> {
+                a: Value::known(Fp::from(a)),
+                b: Value::known(Fp::from(b)),
+                num_bits,
+            };
+
+            let prover = MockProver::run(10, &circuit, public_inputs(a, b, num_bits)).unwrap();
+
+            prover.assert_satisfied();
+
+            let mut wrong = public_inputs(a, b, num_bits);
+            wrong[0][2] += Fp::from(1);
+
+            let prover = MockProver::run(10, &circuit, wrong).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+
+        // Operands wider than the word are rejected by the decomposition.
+        let circuit = BitwiseCircuit::<Fp, 4> {
+            a: Value::known(Fp::from(1 << 32)),
+            b: Value::known(Fp::from(0)),
+            num_bits: 32,
+        };
+
+        let prover = MockProver::run(10, &circuit, public_inputs(0, 0, 32)).unwrap();
+
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_bitwise_bytes() {
+        let circuit = BitwiseCircuit::<Fp, 8> {
+            a: Value::known(Fp::from(0xa5)),
+            b: Value::known(Fp::from(0x3c)),
+            num_bits: 8,
+        };
+
+        let prover = MockProver::run(18, &circuit, public_inputs(0xa5, 0x3c, 8)).unwrap();
+
+        prover.assert_satisfied();
+    }
+}
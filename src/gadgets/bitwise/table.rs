@@ -0,0 +1,60 @@
+use std::marker::PhantomData;
+use halo2_proofs::circuit::{Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{ConstraintSystem, Error, TableColumn};
+
+use crate::gadgets::bitwise::BitwiseOp;
+
+/// A table of `(op, a, b, a op b)` for every operation and all K-bit `a` and `b`.
+#[derive(Clone, Debug)]
+pub struct BitwiseTable<F: PrimeField> {
+    pub op: TableColumn,
+    pub a: TableColumn,
+    pub b: TableColumn,
+    pub out: TableColumn,
+    lookup_bits: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> BitwiseTable<F> {
+    pub fn configure(meta: &mut ConstraintSystem<F>, lookup_bits: usize) -> Self {
+        let op = meta.lookup_table_column();
+        let a = meta.lookup_table_column();
+        let b = meta.lookup_table_column();
+        let out = meta.lookup_table_column();
+
+        Self {
+            op,
+            a,
+            b,
+            out,
+            lookup_bits,
+            _marker: PhantomData
+        }
+    }
+
+    pub fn lookup_bits(&self) -> usize {
+        self.lookup_bits
+    }
+
+    pub fn load(&self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(|| "Assign bitwise table", |mut table| {
+            let mut row = 0;
+
+            for op in [BitwiseOp::And, BitwiseOp::Or, BitwiseOp::Xor] {
+                for a in 0..1u64 << self.lookup_bits {
+                    for b in 0..1u64 << self.lookup_bits {
+                        table.assign_cell(|| "Assign op", self.op, row, || Value::known(op.tag::<F>()))?;
+                        table.assign_cell(|| "Assign a", self.a, row, || Value::known(F::from(a)))?;
+                        table.assign_cell(|| "Assign b", self.b, row, || Value::known(F::from(b)))?;
+                        table.assign_cell(|| "Assign a op b", self.out, row, || Value::known(F::from(op.apply(a, b))))?;
+
+                        row += 1;
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
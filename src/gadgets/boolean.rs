@@ -0,0 +1,396 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Selector, VirtualCells};
+use halo2_proofs::poly::Rotation;
+
+/// A cell that is constrained to be 0 or 1.
+///
+/// It can only be obtained from a [`BooleanChip`], either by witnessing a boolean or as
+/// the output of a logic gate on other `Bool`s.
+#[derive(Clone, Debug)]
+pub struct Bool<F: Field>(AssignedCell<F, F>);
+
+impl<F: Field> Bool<F> {
+    pub fn cell(&self) -> &AssignedCell<F, F> {
+        &self.0
+    }
+
+    pub fn value(&self) -> Value<&F> {
+        self.0.value()
+    }
+}
+
+impl<F: Field> From<Bool<F>> for AssignedCell<F, F> {
+    fn from(bool: Bool<F>) -> Self {
+        bool.0
+    }
+}
+
+/// Logic gates over boolean cells. Each operation uses a single row, with one selector
+/// per gate:
+///
+/// ```text
+///  |  a  |  b  |  out  | q_bool | q_not | q_and | q_or | q_xor |
+///  ---------------------------------------------------------------
+///  |  a  |  b  |  out  |   .    |   .   |   .   |  .   |   .   |
+/// ```
+///
+/// ```text
+///  q_bool:  a * (1 - a) = 0
+///  q_not:   out = 1 - a
+///  q_and:   out = a * b
+///  q_or:    out = a + b - a * b
+///  q_xor:   out = a + b - 2 * a * b
+/// ```
+///
+/// The logic gates only produce booleans from boolean inputs, which the [`Bool`] type
+/// guarantees.
+#[derive(Clone, Debug)]
+pub struct BooleanConfig {
+    advice: [Column<Advice>; 3],
+    bool_selector: Selector,
+    not_selector: Selector,
+    and_selector: Selector,
+    or_selector: Selector,
+    xor_selector: Selector,
+}
+
+pub struct BooleanChip<F: Field> {
+    config: BooleanConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Chip<F> for BooleanChip<F> {
+    type Config = BooleanConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> BooleanChip<F> {
+    pub fn construct(config: BooleanConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> BooleanConfig {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let fixed_column = meta.fixed_column();
+
+        meta.enable_constant(fixed_column);
+
+        let bool_selector = meta.selector();
+        let not_selector = meta.selector();
+        let and_selector = meta.selector();
+        let or_selector = meta.selector();
+        let xor_selector = meta.selector();
+
+        let query = |meta: &mut VirtualCells<'_, F>, column: usize| meta.query_advice(advice[column], Rotation::cur());
+
+        let one = Expression::Constant(F::ONE);
+
+        meta.create_gate("bool", |meta| {
+            let bool_selector = meta.query_selector(bool_selector);
+            let a = query(meta, 0);
+
+            Constraints::with_selector(bool_selector, [("a is boolean", a.clone() * (one.clone() - a))])
+        });
+
+        meta.create_gate("not", |meta| {
+            let not_selector = meta.query_selector(not_selector);
+            let a = query(meta, 0);
+            let out = query(meta, 2);
+
+            Constraints::with_selector(not_selector, [("out = 1 - a", one.clone() - a - out)])
+        });
+
+        meta.create_gate("and", |meta| {
+            let and_selector = meta.query_selector(and_selector);
+            let a = query(meta, 0);
+            let b = query(meta, 1);
+            let out = query(meta, 2);
+
+            Constraints::with_selector(and_selector, [("out = a * b", a * b - out)])
+        });
+
+        meta.create_gate("or", |meta| {
+            let or_selector = meta.query_selector(or_selector);
+            let a = query(meta, 0);
+            let b = query(meta, 1);
+            let out = query(meta, 2);
+
+            Constraints::with_selector(or_selector, [("out = a + b - a * b", a.clone() + b.clone() - a * b - out)])
+        });
+
+        meta.create_gate("xor", |meta| {
+            let xor_selector = meta.query_selector(xor_selector);
+            let a = query(meta, 0);
+            let b = query(meta, 1);
+            let out = query(meta, 2);
+
+            Constraints::with_selector(xor_selector, [(
+                "out = a + b - 2 * a * b",
+                a.clone() + b.clone() - Expression::Constant(F::ONE.double()) * a * b - out,
+            )])
+        });
+
+        BooleanConfig {
+            advice,
+            bool_selector,
+            not_selector,
+            and_selector,
+            or_selector,
+            xor_selector,
+        }
+    }
+
+    /// Witnesses `value`, constraining it to be boolean.
+    pub fn assign_bool(&self, mut layouter: impl Layouter<F>, value: Value<F>) -> Result<Bool<F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "assign bool",
+            |mut region| {
+                config.bool_selector.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "a", config.advice[0], 0, || value).map(Bool)
+            },
+        )
+    }
+
+    /// Constrains an existing cell to be boolean.
+    pub fn constrain_bool(&self, mut layouter: impl Layouter<F>, cell: AssignedCell<F, F>) -> Result<Bool<F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "constrain bool",
+            |mut region| {
+                config.bool_selector.enable(&mut region, 0)?;
+
+                cell.copy_advice(|| "a", &mut region, config.advice[0], 0).map(Bool)
+            },
+        )
+    }
+
+    fn logic_gate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        selector: Selector,
+        a: &Bool<F>,
+        b: Option<&Bool<F>>,
+        op: impl Fn(F, F) -> F,
+    ) -> Result<Bool<F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "logic gate",
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+
+                let b_value = match b {
+                    Some(b) => b.0.copy_advice(|| "b", &mut region, config.advice[1], 0)?.value().copied(),
+                    None => Value::known(F::ZERO),
+                };
+
+                let out = a.value().copied().zip(b_value).map(|(a, b)| op(a, b));
+
+                region.assign_advice(|| "out", config.advice[2], 0, || out).map(Bool)
+            },
+        )
+    }
+
+    pub fn not(&self, layouter: impl Layouter<F>, a: &Bool<F>) -> Result<Bool<F>, Error> {
+        self.logic_gate(layouter, self.config.not_selector, a, None, |a, _| F::ONE - a)
+    }
+
+    pub fn and(&self, layouter: impl Layouter<F>, a: &Bool<F>, b: &Bool<F>) -> Result<Bool<F>, Error> {
+        self.logic_gate(layouter, self.config.and_selector, a, Some(b), |a, b| a * b)
+    }
+
+    pub fn or(&self, layouter: impl Layouter<F>, a: &Bool<F>, b: &Bool<F>) -> Result<Bool<F>, Error> {
+        self.logic_gate(layouter, self.config.or_selector, a, Some(b), |a, b| a + b - a * b)
+    }
+
+    pub fn xor(&self, layouter: impl Layouter<F>, a: &Bool<F>, b: &Bool<F>) -> Result<Bool<F>, Error> {
+        self.logic_gate(layouter, self.config.xor_selector, a, Some(b), |a, b| a + b - (a * b).double())
+    }
+
+    /// Returns the conjunction of `values`, which must not be empty.
+    pub fn and_many(&self, mut layouter: impl Layouter<F>, values: &[Bool<F>]) -> Result<Bool<F>, Error> {
+        let (first, rest) = values.split_first().expect("and_many needs at least one value");
+
+        rest.iter().enumerate().try_fold(first.clone(), |acc, (i, value)| {
+            self.and(layouter.namespace(|| format!("and {}", i)), &acc, value)
+        })
+    }
+
+    /// Returns the disjunction of `values`, which must not be empty.
+    pub fn or_many(&self, mut layouter: impl Layouter<F>, values: &[Bool<F>]) -> Result<Bool<F>, Error> {
+        let (first, rest) = values.split_first().expect("or_many needs at least one value");
+
+        rest.iter().enumerate().try_fold(first.clone(), |acc, (i, value)| {
+            self.or(layouter.namespace(|| format!("or {}", i)), &acc, value)
+        })
+    }
+
+    pub fn assert_true(&self, mut layouter: impl Layouter<F>, a: &Bool<F>) -> Result<(), Error> {
+        layouter.assign_region(|| "assert true", |mut region| region.constrain_constant(a.0.cell(), F::ONE))
+    }
+
+    pub fn assert_false(&self, mut layouter: impl Layouter<F>, a: &Bool<F>) -> Result<(), Error> {
+        layouter.assign_region(|| "assert false", |mut region| region.constrain_constant(a.0.cell(), F::ZERO))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::{MockProver, VerifyFailure};
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    struct BooleanCircuit<F: Field> {
+        values: Vec<Value<F>>,
+        // Whether to assert that all values are true (`Some(true)`) or false (`Some(false)`).
+        assert: Option<bool>,
+    }
+
+    impl<F: Field> Circuit<F> for BooleanCircuit<F> {
+        type Config = (BooleanConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                assert: self.assert,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            (BooleanChip::configure(meta, advice), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            let chip = BooleanChip::construct(config);
+
+            let values = self.values
+                .iter()
+                .map(|value| chip.assign_bool(layouter.namespace(|| "value"), *value))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            match self.assert {
+                Some(true) => {
+                    for value in values.iter() {
+                        chip.assert_true(layouter.namespace(|| "assert true"), value)?;
+                    }
+                }
+                Some(false) => {
+                    for value in values.iter() {
+                        chip.assert_false(layouter.namespace(|| "assert false"), value)?;
+                    }
+                }
+                None => {}
+            }
+
+            // Expose not(a), and, or and xor of the first two values, then and_many and
+            // or_many of all of them.
+            let outputs = [
+                chip.not(layouter.namespace(|| "not"), &values[0])?,
+                chip.and(layouter.namespace(|| "and"), &values[0], &values[1])?,
+                chip.or(layouter.namespace(|| "or"), &values[0], &values[1])?,
+                chip.xor(layouter.namespace(|| "xor"), &values[0], &values[1])?,
+                chip.and_many(layouter.namespace(|| "and many"), &values)?,
+                chip.or_many(layouter.namespace(|| "or many"), &values)?,
+            ];
+
+            for (row, output) in outputs.iter().enumerate() {
+                layouter.constrain_instance(output.cell().cell(), instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn prover(values: &[u64], assert: Option<bool>) -> MockProver<Fp> {
+        let values: Vec<_> = values.iter().map(|&value| Fp::from(value)).collect();
+
+        let circuit = BooleanCircuit {
+            values: values.iter().map(|&value| Value::known(value)).collect(),
+            assert,
+        };
+
+        // The outputs as computed by the gate formulas, which agree with the boolean
+        // operations on 0 and 1.
+        let and = |a: Fp, b: Fp| a * b;
+        let or = |a: Fp, b: Fp| a + b - a * b;
+
+        let (a, b) = (values[0], values[1]);
+        let expected = vec![
+            Fp::ONE - a,
+            and(a, b),
+            or(a, b),
+            a + b - (a * b).double(),
+            values[1..].iter().fold(values[0], |acc, &value| and(acc, value)),
+            values[1..].iter().fold(values[0], |acc, &value| or(acc, value)),
+        ];
+
+        MockProver::run(5, &circuit, vec![expected]).unwrap()
+    }
+
+    fn run(values: &[u64], assert: Option<bool>) -> bool {
+        prover(values, assert).verify().is_ok()
+    }
+
+    #[test]
+    fn test_logic_gates() {
+        for (a, b) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            assert!(run(&[a, b], None));
+            assert!(run(&[a, b, 1], None));
+            assert!(run(&[a, b, 0, 1], None));
+        }
+
+        // A non-boolean witness is rejected, and by the booleanity constraint alone since the
+        // public outputs match what the gates compute on it.
+        let failures = prover(&[2, 0], None).verify().unwrap_err();
+
+        assert!(!failures.is_empty());
+        assert!(failures.iter().all(|failure| matches!(
+            failure,
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if *constraint == ((0, "bool").into(), 0, "a is boolean").into()
+        )));
+    }
+
+    #[test]
+    fn test_assert_true_false() {
+        assert!(run(&[1, 1, 1], Some(true)));
+        assert!(!run(&[1, 0, 1], Some(true)));
+
+        assert!(run(&[0, 0], Some(false)));
+        assert!(!run(&[0, 1], Some(false)));
+    }
+}
@@ -0,0 +1,227 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter};
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Selector};
+use halo2_proofs::poly::Rotation;
+
+/// Field inversion and division, proving that the divisor is non-zero.
+///
+/// ```text
+///  |  num  |  den  |  den_inv  |  out  | q_div |
+///  ---------------------------------------------
+///  |   a   |   b   |  b^{-1}   | a / b |   1   |
+/// ```
+///
+/// ```text
+///  b * b^{-1} = 1,   out = a * b^{-1}
+/// ```
+///
+/// The first constraint has no solution for b = 0. When the divisor is a known zero at
+/// synthesis time the chip reports `Error::Synthesis` instead of assigning a witness that
+/// could never satisfy it.
+#[derive(Clone, Debug)]
+pub struct InverseConfig<F: Field> {
+    advice: [Column<Advice>; 4],
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+pub struct InverseChip<F: Field> {
+    config: InverseConfig<F>,
+}
+
+impl<F: Field> Chip<F> for InverseChip<F> {
+    type Config = InverseConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> InverseChip<F> {
+    pub fn construct(config: InverseConfig<F>) -> Self {
+        InverseChip { config }
+    }
+
+    /// Configures the chip on `[num, den, den_inv, out]`.
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 4]) -> InverseConfig<F> {
+        let [num, den, den_inv, out] = advice;
+
+        meta.enable_equality(num);
+        meta.enable_equality(den);
+        meta.enable_equality(out);
+
+        let fixed_column = meta.fixed_column();
+
+        meta.enable_constant(fixed_column);
+
+        let selector = meta.selector();
+
+        meta.create_gate("div", |meta| {
+            let selector = meta.query_selector(selector);
+            let num = meta.query_advice(num, Rotation::cur());
+            let den = meta.query_advice(den, Rotation::cur());
+            let den_inv = meta.query_advice(den_inv, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            Constraints::with_selector(selector, [
+                ("den * den_inv = 1", den * den_inv.clone() - Expression::Constant(F::ONE)),
+                ("out = num * den_inv", num * den_inv - out),
+            ])
+        });
+
+        InverseConfig {
+            advice,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns `num / den`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Synthesis` if `den` is known to be zero, before any witness is
+    /// assigned for it. Without witnesses (e.g. at keygen) nothing is checked here and
+    /// the `den * den_inv = 1` constraint rejects a zero divisor in the proof.
+    pub fn div(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: AssignedCell<F, F>,
+        den: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "div",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                num.copy_advice(|| "num", &mut region, config.advice[0], 0)?;
+                den.copy_advice(|| "den", &mut region, config.advice[1], 0)?;
+
+                // Surface a zero divisor now rather than as an unsatisfied gate.
+                den.value().error_if_known_and(|den| den.is_zero_vartime())?;
+
+                let den_inv = den.value().map(|den| den.invert().unwrap());
+
+                region.assign_advice(|| "den_inv", config.advice[2], 0, || den_inv)?;
+
+                let out = num.value().copied() * den_inv;
+
+                region.assign_advice(|| "out", config.advice[3], 0, || out)
+            },
+        )
+    }
+
+    /// Returns `value^{-1}`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Synthesis` if `value` is known to be zero, as for [`InverseChip::div`].
+    pub fn invert(&self, mut layouter: impl Layouter<F>, value: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let one = layouter.assign_region(
+            || "one",
+            |mut region| region.assign_advice_from_constant(|| "one", config.advice[0], 0, F::ONE),
+        )?;
+
+        self.div(layouter.namespace(|| "1 / value"), one, value)
+    }
+
+    /// Constrains `value` to be non-zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Synthesis` if `value` is known to be zero, as for [`InverseChip::div`].
+    pub fn assert_nonzero(&self, layouter: impl Layouter<F>, value: AssignedCell<F, F>) -> Result<(), Error> {
+        self.invert(layouter, value).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Value};
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    struct InverseCircuit<F: Field> {
+        a: Value<F>,
+        b: Value<F>,
+    }
+
+    impl<F: Field> Circuit<F> for InverseCircuit<F> {
+        type Config = (InverseConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: Value::unknown(),
+                b: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            (InverseChip::configure(meta, advice), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            let (a, b) = layouter.assign_region(|| "assign values", |mut region| {
+                let a = region.assign_advice(|| "a", config.advice[0], 0, || self.a)?;
+                let b = region.assign_advice(|| "b", config.advice[1], 0, || self.b)?;
+
+                Ok((a, b))
+            })?;
+
+            let chip = InverseChip::construct(config);
+
+            chip.assert_nonzero(layouter.namespace(|| "assert nonzero"), b.clone())?;
+
+            let inverse = chip.invert(layouter.namespace(|| "invert"), b.clone())?;
+            let quotient = chip.div(layouter.namespace(|| "div"), a, b)?;
+
+            layouter.constrain_instance(inverse.cell(), instance, 0)?;
+            layouter.constrain_instance(quotient.cell(), instance, 1)
+        }
+    }
+
+    #[test]
+    fn test_div() {
+        let (a, b) = (Fp::from(21), Fp::from(4));
+        let b_inv = b.invert().unwrap();
+
+        let circuit = InverseCircuit { a: Value::known(a), b: Value::known(b) };
+
+        let prover = MockProver::run(4, &circuit, vec![vec![b_inv, a * b_inv]]).unwrap();
+
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(4, &circuit, vec![vec![b_inv, a * b_inv + Fp::ONE]]).unwrap();
+
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_zero_divisor() {
+        let circuit = InverseCircuit { a: Value::known(Fp::from(21)), b: Value::known(Fp::ZERO) };
+
+        assert!(matches!(MockProver::run(4, &circuit, vec![vec![Fp::ZERO, Fp::ZERO]]), Err(Error::Synthesis)));
+    }
+}
@@ -0,0 +1,184 @@
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter};
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::gadgets::is_zero::{Instructions, IsZeroChip, IsZeroConfig};
+
+/// Witnesses `out = [a == b]` in its own column, so that it can be copied into other
+/// regions or exposed as a public input.
+///
+/// ```text
+///  |  a  |  b  |  out  |  (a - b)^{-1}  | q_is_equal |
+///  ----------------------------------------------------
+///  |  a  |  b  |  out  |  (a - b)^{-1}  |     1      |
+/// ```
+///
+/// The `is zero` gate on `a - b` makes `IsZeroConfig::expr()` equal to `[a == b]`, and
+/// `out` is constrained to that expression.
+#[derive(Clone, Debug)]
+pub struct IsEqualConfig<F: Field> {
+    advice: [Column<Advice>; 3],
+    selector: Selector,
+    is_zero: IsZeroConfig<F>,
+}
+
+pub struct IsEqualChip<F: Field> {
+    config: IsEqualConfig<F>,
+}
+
+impl<F: Field> Chip<F> for IsEqualChip<F> {
+    type Config = IsEqualConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> IsEqualChip<F> {
+    pub fn construct(config: IsEqualConfig<F>) -> Self {
+        IsEqualChip { config }
+    }
+
+    /// Configures the chip on `[a, b, out]`, with `a_minus_b_inv` holding the inverse
+    /// witnessed by [`IsZeroChip`].
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        a_minus_b_inv: Column<Advice>,
+    ) -> IsEqualConfig<F> {
+        let [a, b, out] = advice;
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let selector = meta.selector();
+
+        let is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(a, Rotation::cur()) - meta.query_advice(b, Rotation::cur()),
+            a_minus_b_inv,
+        );
+
+        meta.create_gate("is equal", |meta| {
+            let selector = meta.query_selector(selector);
+            let out = meta.query_advice(out, Rotation::cur());
+
+            Constraints::with_selector(selector, [("out = is_zero(a - b)", out - is_zero.expr())])
+        });
+
+        IsEqualConfig {
+            advice,
+            selector,
+            is_zero,
+        }
+    }
+
+    /// Returns a boolean cell that is 1 iff `a == b`.
+    pub fn is_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        let is_zero_chip = IsZeroChip::construct(config.is_zero.clone());
+
+        layouter.assign_region(
+            || "is equal",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                let difference = a.value().copied() - b.value().copied();
+
+                is_zero_chip.assign(&mut region, difference, 0)?;
+
+                let out = difference.map(|difference| if difference.is_zero_vartime() { F::ONE } else { F::ZERO });
+
+                region.assign_advice(|| "out", config.advice[2], 0, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Value};
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    struct IsEqualCircuit<F: Field> {
+        a: Value<F>,
+        b: Value<F>,
+    }
+
+    impl<F: Field> Circuit<F> for IsEqualCircuit<F> {
+        type Config = (IsEqualConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: Value::unknown(),
+                b: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let a_minus_b_inv = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            (IsEqualChip::configure(meta, advice, a_minus_b_inv), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            let (a, b) = layouter.assign_region(|| "assign values", |mut region| {
+                let a = region.assign_advice(|| "a", config.advice[0], 0, || self.a)?;
+                let b = region.assign_advice(|| "b", config.advice[1], 0, || self.b)?;
+
+                Ok((a, b))
+            })?;
+
+            let chip = IsEqualChip::construct(config);
+
+            let out = chip.is_equal(layouter.namespace(|| "is equal"), a, b)?;
+
+            layouter.constrain_instance(out.cell(), instance, 0)
+        }
+    }
+
+    #[test]
+    fn test_is_equal() {
+        for (a, b) in [(0, 0), (7, 7), (7, 8), (0, 1), (u64::MAX, u64::MAX - 1)] {
+            let circuit = IsEqualCircuit {
+                a: Value::known(Fp::from(a)),
+                b: Value::known(Fp::from(b)),
+            };
+
+            let prover = MockProver::run(4, &circuit, vec![vec![Fp::from((a == b) as u64)]]).unwrap();
+
+            prover.assert_satisfied();
+
+            let prover = MockProver::run(4, &circuit, vec![vec![Fp::from((a != b) as u64)]]).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use halo2_proofs::arithmetic::Field;
-use halo2_proofs::circuit::{Chip, Region, Value};
-use halo2_proofs::plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells};
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Region, Value};
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Selector, VirtualCells};
 use halo2_proofs::poly::Rotation;
 
 #[derive(Clone, Debug)]
@@ -86,3 +86,159 @@ impl<F: Field> Instructions<F> for IsZeroChip<F> {
         Ok(())
     }
 }
+
+/// An is-zero chip that owns its value and output columns, for use where the caller has
+/// no custom gate to embed `IsZeroConfig::expr()` in:
+///
+/// ```text
+///  |  value  |  value_inv  |  output  | q_is_zero |
+///  ------------------------------------------------
+///  |    v    |   v^{-1}    |  [v = 0] |     1     |
+/// ```
+#[derive(Clone, Debug)]
+pub struct StandaloneIsZeroConfig<F: Field> {
+    value: Column<Advice>,
+    output: Column<Advice>,
+    selector: Selector,
+    is_zero: IsZeroConfig<F>,
+}
+
+pub struct StandaloneIsZeroChip<F: Field> {
+    config: StandaloneIsZeroConfig<F>,
+}
+
+impl<F: Field> Chip<F> for StandaloneIsZeroChip<F> {
+    type Config = StandaloneIsZeroConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> StandaloneIsZeroChip<F> {
+    pub fn construct(config: StandaloneIsZeroConfig<F>) -> Self {
+        StandaloneIsZeroChip { config }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> StandaloneIsZeroConfig<F> {
+        let value = meta.advice_column();
+        let value_inv = meta.advice_column();
+        let output = meta.advice_column();
+
+        meta.enable_equality(value);
+        meta.enable_equality(output);
+
+        let selector = meta.selector();
+
+        let is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            value_inv,
+        );
+
+        meta.create_gate("is zero output", |meta| {
+            let selector = meta.query_selector(selector);
+            let output = meta.query_advice(output, Rotation::cur());
+
+            Constraints::with_selector(selector, [("output = is_zero(value)", output - is_zero.expr())])
+        });
+
+        StandaloneIsZeroConfig {
+            value,
+            output,
+            selector,
+            is_zero,
+        }
+    }
+
+    /// Copies `value` into the chip's own columns and returns a boolean cell that is 1 iff
+    /// `value` is zero.
+    pub fn is_zero(&self, mut layouter: impl Layouter<F>, value: AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let is_zero_chip = IsZeroChip::construct(config.is_zero.clone());
+
+        layouter.assign_region(
+            || "is zero",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                value.copy_advice(|| "value", &mut region, config.value, 0)?;
+
+                is_zero_chip.assign(&mut region, value.value().copied(), 0)?;
+
+                let output = value.value().map(|value| if value.is_zero_vartime() { F::ONE } else { F::ZERO });
+
+                region.assign_advice(|| "output", config.output, 0, || output)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    struct IsZeroCircuit<F: Field> {
+        value: Value<F>,
+    }
+
+    impl<F: Field> Circuit<F> for IsZeroCircuit<F> {
+        type Config = (StandaloneIsZeroConfig<F>, Column<Advice>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { value: Value::unknown() }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(advice);
+            meta.enable_equality(instance);
+
+            (StandaloneIsZeroChip::configure(meta), advice, instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, advice, instance) = config;
+
+            let value = layouter.assign_region(|| "assign value", |mut region| {
+                region.assign_advice(|| "value", advice, 0, || self.value)
+            })?;
+
+            let chip = StandaloneIsZeroChip::construct(config);
+
+            let output = chip.is_zero(layouter.namespace(|| "is zero"), value)?;
+
+            layouter.constrain_instance(output.cell(), instance, 0)
+        }
+    }
+
+    #[test]
+    fn test_standalone_is_zero() {
+        for value in [0, 1, 5, u64::MAX] {
+            let circuit = IsZeroCircuit { value: Value::known(Fp::from(value)) };
+
+            let prover = MockProver::run(4, &circuit, vec![vec![Fp::from((value == 0) as u64)]]).unwrap();
+
+            prover.assert_satisfied();
+
+            let prover = MockProver::run(4, &circuit, vec![vec![Fp::from((value != 0) as u64)]]).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+    }
+}
@@ -0,0 +1,480 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Region, Value};
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+/// The coefficients of a single row of the standard PLONK gate.
+#[derive(Clone, Copy, Debug)]
+pub struct Coefficients<F: Field> {
+    pub q_l: F,
+    pub q_r: F,
+    pub q_o: F,
+    pub q_m: F,
+    pub q_c: F,
+}
+
+impl<F: Field> Default for Coefficients<F> {
+    fn default() -> Self {
+        Self {
+            q_l: F::ZERO,
+            q_r: F::ZERO,
+            q_o: F::ZERO,
+            q_m: F::ZERO,
+            q_c: F::ZERO,
+        }
+    }
+}
+
+/// The standard ("vanilla") PLONK gate, with all coefficients held in fixed columns:
+///
+/// ```text
+///  q_l * a + q_r * b + q_o * c + q_m * a * b + q_c = 0
+/// ```
+///
+/// ```text
+///  |  a  |  b  |  c  |  q_l  |  q_r  |  q_o  |  q_m  |  q_c  | q_plonk |
+///  ----------------------------------------------------------------------
+///  |  a  |  b  |  c  |  q_l  |  q_r  |  q_o  |  q_m  |  q_c  |    1    |
+/// ```
+///
+/// Every operation takes one row on the same three advice columns, so the chip only
+/// ever creates a single custom gate. Operations that produce a value use `q_o = -1`,
+/// which makes `c = q_l * a + q_r * b + q_m * a * b + q_c`.
+#[derive(Clone, Debug)]
+pub struct PlonkConfig {
+    advice: [Column<Advice>; 3],
+    q_l: Column<Fixed>,
+    q_r: Column<Fixed>,
+    q_o: Column<Fixed>,
+    q_m: Column<Fixed>,
+    q_c: Column<Fixed>,
+    selector: Selector,
+}
+
+pub struct PlonkChip<F: Field> {
+    config: PlonkConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Chip<F> for PlonkChip<F> {
+    type Config = PlonkConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> PlonkChip<F> {
+    pub fn construct(config: PlonkConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chip on `[a, b, c]`; the five coefficient columns are allocated
+    /// here.
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 3]) -> PlonkConfig {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let [q_l, q_r, q_o, q_m, q_c] = [(); 5].map(|_| meta.fixed_column());
+        let selector = meta.selector();
+
+        meta.create_gate("plonk", |meta| {
+            let selector = meta.query_selector(selector);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let c = meta.query_advice(advice[2], Rotation::cur());
+            let q_l = meta.query_fixed(q_l);
+            let q_r = meta.query_fixed(q_r);
+            let q_o = meta.query_fixed(q_o);
+            let q_m = meta.query_fixed(q_m);
+            let q_c = meta.query_fixed(q_c);
+
+            Constraints::with_selector(selector, [
+                q_l * a.clone() + q_r * b.clone() + q_o * c + q_m * a * b + q_c,
+            ])
+        });
+
+        PlonkConfig {
+            advice,
+            q_l,
+            q_r,
+            q_o,
+            q_m,
+            q_c,
+            selector,
+        }
+    }
+
+    pub fn load_private(&self, mut layouter: impl Layouter<F>, value: Value<F>) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", config.advice[0], 0, || value),
+        )
+    }
+
+    /// Witnesses `constant` in `a`, fixed by the row `a - constant = 0`.
+    pub fn load_constant(&self, mut layouter: impl Layouter<F>, constant: F) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let coeffs = Coefficients {
+            q_l: F::ONE,
+            q_c: -constant,
+            ..Default::default()
+        };
+
+        layouter.assign_region(
+            || "load constant",
+            |mut region| {
+                self.assign_coefficients(&mut region, coeffs)?;
+
+                region.assign_advice(|| "b", config.advice[1], 0, || Value::known(F::ZERO))?;
+                region.assign_advice(|| "c", config.advice[2], 0, || Value::known(F::ZERO))?;
+                region.assign_advice(|| "constant", config.advice[0], 0, || Value::known(constant))
+            },
+        )
+    }
+
+    fn assign_coefficients(&self, region: &mut Region<'_, F>, coeffs: Coefficients<F>) -> Result<(), Error> {
+        let config = self.config();
+
+        config.selector.enable(region, 0)?;
+
+        for (name, column, coeff) in [
+            ("q_l", config.q_l, coeffs.q_l),
+            ("q_r", config.q_r, coeffs.q_r),
+            ("q_o", config.q_o, coeffs.q_o),
+            ("q_m", config.q_m, coeffs.q_m),
+            ("q_c", config.q_c, coeffs.q_c),
+        ] {
+            region.assign_fixed(|| name, column, 0, || Value::known(coeff))?;
+        }
+
+        Ok(())
+    }
+
+    /// Constrains `q_l * a + q_r * b + q_o * c + q_m * a * b + q_c = 0` on three existing
+    /// cells.
+    pub fn constrain(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        c: &AssignedCell<F, F>,
+        coeffs: Coefficients<F>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "constrain",
+            |mut region| {
+                self.assign_coefficients(&mut region, coeffs)?;
+
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                c.copy_advice(|| "c", &mut region, config.advice[2], 0)?;
+
+                Ok(())
+            },
+        )
+    }
+
+    /// Returns `q_l * a + q_r * b + q_m * a * b + q_c`; `coeffs.q_o` is ignored and set
+    /// to -1.
+    pub fn combine(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        coeffs: Coefficients<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let coeffs = Coefficients { q_o: -F::ONE, ..coeffs };
+
+        layouter.assign_region(
+            || "combine",
+            |mut region| {
+                self.assign_coefficients(&mut region, coeffs)?;
+
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                let out = a.value().zip(b.value()).map(|(a, b)| {
+                    coeffs.q_l * a + coeffs.q_r * b + coeffs.q_m * a * b + coeffs.q_c
+                });
+
+                region.assign_advice(|| "c", config.advice[2], 0, || out)
+            },
+        )
+    }
+
+    pub fn add(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        let coeffs = Coefficients { q_l: F::ONE, q_r: F::ONE, ..Default::default() };
+
+        self.combine(layouter, a, b, coeffs)
+    }
+
+    pub fn sub(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        let coeffs = Coefficients { q_l: F::ONE, q_r: -F::ONE, ..Default::default() };
+
+        self.combine(layouter, a, b, coeffs)
+    }
+
+    pub fn mul(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        let coeffs = Coefficients { q_m: F::ONE, ..Default::default() };
+
+        self.combine(layouter, a, b, coeffs)
+    }
+
+    /// Returns `scale * a + offset` in a single row.
+    pub fn affine(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, scale: F, offset: F) -> Result<AssignedCell<F, F>, Error> {
+        let coeffs = Coefficients { q_l: scale, q_c: offset, ..Default::default() };
+
+        // `b` is unused, so `a` is copied there as well.
+        self.combine(layouter, a, a, coeffs)
+    }
+
+    /// Returns `sum_i coeff_i * x_i + constant`. The first two terms share a row and every
+    /// further term adds one; an empty slice yields the constant.
+    pub fn linear_combination(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[(F, AssignedCell<F, F>)],
+        constant: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        match terms {
+            [] => self.load_constant(layouter.namespace(|| "constant"), constant),
+            [(coeff, x)] => self.affine(layouter.namespace(|| "term 0"), x, *coeff, constant),
+            [(c_0, x_0), (c_1, x_1), rest @ ..] => {
+                let coeffs = Coefficients { q_l: *c_0, q_r: *c_1, q_c: constant, ..Default::default() };
+                let first = self.combine(layouter.namespace(|| "terms 0 and 1"), x_0, x_1, coeffs)?;
+
+                rest.iter().enumerate().try_fold(first, |acc, (i, (coeff, x))| {
+                    let coeffs = Coefficients { q_l: F::ONE, q_r: *coeff, ..Default::default() };
+
+                    self.combine(layouter.namespace(|| format!("term {}", i + 2)), &acc, x, coeffs)
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    fn configure<F: Field>(meta: &mut ConstraintSystem<F>) -> (PlonkConfig, Column<Instance>) {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        meta.enable_equality(instance);
+
+        (PlonkChip::configure(meta, advice), instance)
+    }
+
+    fn verify(circuit: &impl Circuit<Fp>, expected: Fp) -> bool {
+        MockProver::run(4, circuit, vec![vec![expected]]).unwrap().verify().is_ok()
+    }
+
+    /// The operation applied to the loaded values.
+    #[derive(Clone)]
+    enum Op<F> {
+        Add,
+        Sub,
+        Mul,
+        Affine { scale: F, offset: F },
+        LinearCombination { coeffs: Vec<F>, constant: F },
+        Constant(F),
+        /// Constrains `a * b = c` on the three values and exposes `c`.
+        Constrain,
+    }
+
+    struct PlonkCircuit<F: Field> {
+        op: Op<F>,
+        values: Vec<Value<F>>,
+    }
+
+    impl<F: Field> Circuit<F> for PlonkCircuit<F> {
+        type Config = (PlonkConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                op: self.op.clone(),
+                values: vec![Value::unknown(); self.values.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(&self, (config, instance): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let chip = PlonkChip::construct(config);
+
+            let values = self.values
+                .iter()
+                .map(|value| chip.load_private(layouter.namespace(|| "value"), *value))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let out = match &self.op {
+                Op::Add => chip.add(layouter.namespace(|| "add"), &values[0], &values[1])?,
+                Op::Sub => chip.sub(layouter.namespace(|| "sub"), &values[0], &values[1])?,
+                Op::Mul => chip.mul(layouter.namespace(|| "mul"), &values[0], &values[1])?,
+                Op::Affine { scale, offset } => chip.affine(layouter.namespace(|| "affine"), &values[0], *scale, *offset)?,
+                Op::LinearCombination { coeffs, constant } => {
+                    let terms: Vec<_> = coeffs.iter().copied().zip(values).collect();
+
+                    chip.linear_combination(layouter.namespace(|| "linear combination"), &terms, *constant)?
+                }
+                Op::Constant(constant) => chip.load_constant(layouter.namespace(|| "constant"), *constant)?,
+                Op::Constrain => {
+                    let coeffs = Coefficients { q_m: F::ONE, q_o: -F::ONE, ..Default::default() };
+
+                    chip.constrain(layouter.namespace(|| "a * b = c"), &values[0], &values[1], &values[2], coeffs)?;
+
+                    values[2].clone()
+                }
+            };
+
+            layouter.constrain_instance(out.cell(), instance, 0)
+        }
+    }
+
+    fn run(op: Op<Fp>, values: &[u64], expected: Fp) -> bool {
+        let circuit = PlonkCircuit {
+            op,
+            values: values.iter().map(|&value| Value::known(Fp::from(value))).collect(),
+        };
+
+        verify(&circuit, expected)
+    }
+
+    /// Lays out an `add` row by hand with a wrong `c`, exposing that `c` so that only the
+    /// gate can reject it.
+    #[derive(Default)]
+    struct ForgedAddCircuit<F: Field> {
+        a: Value<F>,
+        b: Value<F>,
+        c: Value<F>,
+    }
+
+    impl<F: Field> Circuit<F> for ForgedAddCircuit<F> {
+        type Config = (PlonkConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(&self, (config, instance): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let chip = PlonkChip::construct(config.clone());
+
+            let coeffs = Coefficients { q_l: F::ONE, q_r: F::ONE, q_o: -F::ONE, ..Default::default() };
+
+            let c = layouter.assign_region(
+                || "forged add",
+                |mut region| {
+                    chip.assign_coefficients(&mut region, coeffs)?;
+
+                    region.assign_advice(|| "a", config.advice[0], 0, || self.a)?;
+                    region.assign_advice(|| "b", config.advice[1], 0, || self.b)?;
+                    region.assign_advice(|| "c", config.advice[2], 0, || self.c)
+                },
+            )?;
+
+            layouter.constrain_instance(c.cell(), instance, 0)
+        }
+    }
+
+    #[test]
+    fn test_add() {
+        assert!(run(Op::Add, &[7, 3], Fp::from(10)));
+        assert!(!run(Op::Add, &[7, 3], Fp::from(11)));
+    }
+
+    #[test]
+    fn test_sub() {
+        assert!(run(Op::Sub, &[7, 3], Fp::from(4)));
+        assert!(!run(Op::Sub, &[7, 3], -Fp::from(4)));
+    }
+
+    #[test]
+    fn test_mul() {
+        assert!(run(Op::Mul, &[7, 3], Fp::from(21)));
+        assert!(!run(Op::Mul, &[7, 3], Fp::from(10)));
+    }
+
+    #[test]
+    fn test_forged_add_rejected() {
+        let circuit = ForgedAddCircuit {
+            a: Value::known(Fp::from(7)),
+            b: Value::known(Fp::from(3)),
+            c: Value::known(Fp::from(11)),
+        };
+
+        assert!(!verify(&circuit, Fp::from(11)));
+
+        let circuit = ForgedAddCircuit { c: Value::known(Fp::from(10)), ..circuit };
+
+        assert!(verify(&circuit, Fp::from(10)));
+    }
+
+    #[test]
+    fn test_affine() {
+        let op = Op::Affine { scale: Fp::from(3), offset: Fp::from(2) };
+
+        assert!(run(op.clone(), &[7], Fp::from(23)));
+        assert!(!run(op, &[7], Fp::from(21)));
+    }
+
+    #[test]
+    fn test_linear_combination() {
+        let op = |coeffs: &[u64]| Op::LinearCombination {
+            coeffs: coeffs.iter().map(|&coeff| Fp::from(coeff)).collect(),
+            constant: -Fp::ONE,
+        };
+
+        // 7 + 2 * 3 + 3 * 5 - 1
+        assert!(run(op(&[1, 2, 3]), &[7, 3, 5], Fp::from(27)));
+        assert!(!run(op(&[1, 2, 3]), &[7, 3, 5], Fp::from(28)));
+
+        // 7 - 1, and the constant alone.
+        assert!(run(op(&[1]), &[7], Fp::from(6)));
+        assert!(run(op(&[]), &[], -Fp::ONE));
+    }
+
+    #[test]
+    fn test_constant() {
+        assert!(run(Op::Constant(Fp::from(3)), &[], Fp::from(3)));
+        assert!(!run(Op::Constant(Fp::from(3)), &[], Fp::from(4)));
+    }
+
+    #[test]
+    fn test_constrain() {
+        assert!(run(Op::Constrain, &[7, 3, 21], Fp::from(21)));
+        assert!(!run(Op::Constrain, &[7, 3, 20], Fp::from(20)));
+    }
+}
@@ -0,0 +1,273 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::gadgets::arithmetic::{ArithmeticChip, ArithmeticConfig};
+use crate::range_check::bits::{BitsChip, BitsConfig};
+
+/// Exponentiation by square-and-multiply, for fixed and for witnessed exponents.
+///
+/// A fixed exponent is known when the circuit is built, so `pow_const` only emits a
+/// squaring for every bit below the leading one and a multiplication for every set bit,
+/// each as one [`ArithmeticChip`] row.
+///
+/// A witnessed exponent is decomposed into `max_bits` bits with the [`BitsChip`], and the
+/// bits are then consumed from the most significant one down, one row each:
+///
+/// ```text
+///  acc_0 = 1,   acc_{i+1} = acc_i^2 * (1 + b_i * (base - 1))
+/// ```
+///
+/// ```text
+///  |  acc  |    bit    |  base  | s_pow_bit |
+///  ------------------------------------------
+///  | acc_0 | b_{n-1}   |  base  |     1     |
+///  | acc_1 | b_{n-2}   |  base  |     1     |
+///  |  ...  |    ...    |  ...   |    ...    |
+///  | acc_n |           |        |     0     |
+/// ```
+#[derive(Clone, Debug)]
+pub struct PowConfig<F: PrimeField> {
+    advice: [Column<Advice>; 3],
+    s_pow_bit: Selector,
+    bits: BitsConfig<F>,
+    arithmetic: ArithmeticConfig,
+}
+
+pub struct PowChip<F: PrimeField> {
+    config: PowConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for PowChip<F> {
+    type Config = PowConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> PowChip<F> {
+    pub fn construct(config: PowConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chip on `[acc, bit, base]` for witnessed exponents; fixed exponents
+    /// go through `arithmetic`, whose constant column also provides `acc_0 = 1`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        bits: BitsConfig<F>,
+        arithmetic: ArithmeticConfig,
+    ) -> PowConfig<F> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let s_pow_bit = meta.selector();
+
+        meta.create_gate("pow bit", |meta| {
+            let s_pow_bit = meta.query_selector(s_pow_bit);
+            let acc = meta.query_advice(advice[0], Rotation::cur());
+            let bit = meta.query_advice(advice[1], Rotation::cur());
+            let base = meta.query_advice(advice[2], Rotation::cur());
+            let acc_next = meta.query_advice(advice[0], Rotation::next());
+
+            let one = Expression::Constant(F::ONE);
+
+            Constraints::with_selector(s_pow_bit, [
+                ("acc_{i+1} = acc_i^2 * base^b_i", acc.clone() * acc * (one.clone() + bit * (base - one)) - acc_next),
+            ])
+        });
+
+        PowConfig {
+            advice,
+            s_pow_bit,
+            bits,
+            arithmetic,
+        }
+    }
+
+    /// Returns `base^exp` for an exponent fixed at circuit construction, given as
+    /// little-endian 64-bit limbs like [`ff::Field::pow_vartime`].
+    pub fn pow_const(
+        &self,
+        mut layouter: impl Layouter<F>,
+        base: &AssignedCell<F, F>,
+        exp: impl AsRef<[u64]>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let arithmetic_chip = ArithmeticChip::construct(self.config().arithmetic.clone());
+
+        let bits: Vec<bool> = exp.as_ref().iter().flat_map(|limb| (0..64).map(move |i| (limb >> i) & 1 == 1)).collect();
+
+        let Some(top) = bits.iter().rposition(|bit| *bit) else {
+            return arithmetic_chip.load_constant(layouter.namespace(|| "base^0"), F::ONE);
+        };
+
+        (0..top).rev().try_fold(base.clone(), |acc, i| {
+            let acc = arithmetic_chip.square(layouter.namespace(|| format!("square {}", i)), &acc)?;
+
+            if bits[i] {
+                arithmetic_chip.mul(layouter.namespace(|| format!("multiply {}", i)), &acc, base)
+            } else {
+                Ok(acc)
+            }
+        })
+    }
+
+    /// Returns `base^exp` for a witnessed exponent, constraining `exp` to `max_bits` bits.
+    pub fn pow_var(
+        &self,
+        mut layouter: impl Layouter<F>,
+        base: &AssignedCell<F, F>,
+        exp: AssignedCell<F, F>,
+        max_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let bits_chip = BitsChip::construct(config.bits.clone());
+        let bits = bits_chip.to_bits(layouter.namespace(|| "exponent bits"), exp, max_bits)?;
+
+        layouter.assign_region(
+            || "pow var",
+            |mut region| {
+                let mut acc = region.assign_advice_from_constant(|| "acc_0", config.advice[0], 0, F::ONE)?;
+
+                for (i, bit) in bits.iter().rev().enumerate() {
+                    config.s_pow_bit.enable(&mut region, i)?;
+
+                    bit.copy_advice(|| "b_i", &mut region, config.advice[1], i)?;
+                    base.copy_advice(|| "base", &mut region, config.advice[2], i)?;
+
+                    let acc_next = acc.value().zip(bit.value()).zip(base.value()).map(|((acc, bit), base)| {
+                        if *bit == F::ONE { acc.square() * base } else { acc.square() }
+                    });
+
+                    acc = region.assign_advice(|| "acc_i", config.advice[0], i + 1, || acc_next)?;
+                }
+
+                Ok(acc)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::arithmetic::Field;
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Value};
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    struct PowCircuit<F: PrimeField> {
+        base: Value<F>,
+        exp: Value<F>,
+        const_exp: [u64; 4],
+        max_bits: usize,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for PowCircuit<F> {
+        type Config = (PowConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                base: Value::unknown(),
+                exp: Value::unknown(),
+                const_exp: self.const_exp,
+                max_bits: self.max_bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let constant = meta.fixed_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            let bits = BitsChip::configure(meta, advice[0], advice[1]);
+            let arithmetic = ArithmeticChip::configure(meta, advice, constant);
+
+            (PowChip::configure(meta, advice, bits, arithmetic), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            let (base, exp) = layouter.assign_region(|| "assign values", |mut region| {
+                let base = region.assign_advice(|| "base", config.advice[0], 0, || self.base)?;
+                let exp = region.assign_advice(|| "exp", config.advice[1], 0, || self.exp)?;
+
+                Ok((base, exp))
+            })?;
+
+            let chip = PowChip::construct(config);
+
+            let pow_const = chip.pow_const(layouter.namespace(|| "pow const"), &base, self.const_exp)?;
+            let pow_var = chip.pow_var(layouter.namespace(|| "pow var"), &base, exp, self.max_bits)?;
+
+            layouter.constrain_instance(pow_const.cell(), instance, 0)?;
+            layouter.constrain_instance(pow_var.cell(), instance, 1)
+        }
+    }
+
+    fn run(base: u64, exp: u64, const_exp: [u64; 4], max_bits: usize, expected: [Fp; 2]) -> bool {
+        let circuit = PowCircuit {
+            base: Value::known(Fp::from(base)),
+            exp: Value::known(Fp::from(exp)),
+            const_exp,
+            max_bits,
+        };
+
+        MockProver::run(10, &circuit, vec![expected.to_vec()]).unwrap().verify().is_ok()
+    }
+
+    #[test]
+    fn test_pow() {
+        for exp in [0, 1, 2, 13, 200, 255] {
+            let expected = Fp::from(3).pow_vartime([exp]);
+
+            assert!(run(3, exp, [exp, 0, 0, 0], 8, [expected, expected]));
+            assert!(!run(3, exp, [exp, 0, 0, 0], 8, [expected, expected + Fp::one()]));
+            assert!(!run(3, exp, [exp, 0, 0, 0], 8, [expected + Fp::one(), expected]));
+        }
+
+        assert!(run(0, 0, [0, 0, 0, 0], 8, [Fp::one(), Fp::one()]));
+        assert!(run(0, 5, [5, 0, 0, 0], 8, [Fp::zero(), Fp::zero()]));
+    }
+
+    #[test]
+    fn test_pow_var_exponent_too_large() {
+        let expected = Fp::from(3).pow_vartime([256]);
+
+        assert!(!run(3, 256, [256, 0, 0, 0], 8, [expected, expected]));
+    }
+
+    #[test]
+    fn test_pow_const_fermat_inverse() {
+        // base^{p-2} = base^{-1}
+        let p_minus_2 = (-Fp::from(2)).to_repr();
+        let limbs = [0, 1, 2, 3].map(|i| u64::from_le_bytes(p_minus_2[8 * i..8 * (i + 1)].try_into().unwrap()));
+
+        let inverse = Fp::from(7).invert().unwrap();
+
+        assert!(run(7, 1, limbs, 8, [inverse, Fp::from(7)]));
+        assert!(!run(7, 1, limbs, 8, [Fp::from(7), Fp::from(7)]));
+    }
+}
@@ -14,10 +14,8 @@ impl<F: PrimeField> RangeCheckConfig<F> {
         value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
         bits: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
         lookup_selector: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
-        lookup_range: usize,
+        lookup_table: RangeCheckTable<F>,
     ) -> Self {
-        let lookup_table = RangeCheckTable::configure(meta, lookup_range);
-
         meta.lookup(|meta| {
             let value = value(meta);
             let bits = bits(meta);
@@ -0,0 +1,325 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+/// Conditional selection between cells.
+///
+/// `select` uses a single row, checking that the condition is boolean:
+///
+/// ```text
+///  |  cond  |  when_true  |  when_false  |  out  | q_select |
+///  ---------------------------------------------------------
+///  |   c    |      t      |      f       |  out  |    1     |
+/// ```
+///
+/// ```text
+///  c * (1 - c) = 0,   out = c * (t - f) + f
+/// ```
+///
+/// `mux` selects `values[index]` with a one-hot vector e_i = [i = index], accumulated
+/// down the rows next to each value and its position i:
+///
+/// ```text
+///  sum_{i+1} = sum_i + e_i,   idx_{i+1} = idx_i + i * e_i,   out_{i+1} = out_i + e_i * v_i
+/// ```
+///
+/// ```text
+///  |  value  |  e  |  sum  |  idx  |  out  | position | q_mux |
+///  -----------------------------------------------------------
+///  |   v_0   | e_0 |   0   |   0   |   0   |    0     |   1   |
+///  |   ...   | ... |  ...  |  ...  |  ...  |   ...    |  ...  |
+///  | v_{n-1} | ... |  ...  |  ...  |  ...  |  n - 1   |   1   |
+///  |         |     |   1   | index |  out  |          |   0   |
+/// ```
+///
+/// With every e_i boolean and sum_n = 1 exactly one e_i is set, and idx_n = index pins
+/// it to the right position; an index outside [0, n) has no valid witness.
+#[derive(Clone, Debug)]
+pub struct SelectConfig<F: PrimeField> {
+    advice: [Column<Advice>; 5],
+    position: Column<Fixed>,
+    select_selector: Selector,
+    mux_selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+pub struct SelectChip<F: PrimeField> {
+    config: SelectConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for SelectChip<F> {
+    type Config = SelectConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField> SelectChip<F> {
+    pub fn construct(config: SelectConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 5]) -> SelectConfig<F> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let fixed_column = meta.fixed_column();
+
+        meta.enable_constant(fixed_column);
+
+        let position = meta.fixed_column();
+
+        let select_selector = meta.selector();
+        let mux_selector = meta.selector();
+
+        let one = Expression::Constant(F::ONE);
+
+        meta.create_gate("select", |meta| {
+            let select_selector = meta.query_selector(select_selector);
+            let cond = meta.query_advice(advice[0], Rotation::cur());
+            let when_true = meta.query_advice(advice[1], Rotation::cur());
+            let when_false = meta.query_advice(advice[2], Rotation::cur());
+            let out = meta.query_advice(advice[3], Rotation::cur());
+
+            Constraints::with_selector(select_selector, [
+                ("cond is boolean", cond.clone() * (one.clone() - cond.clone())),
+                ("out = cond * (t - f) + f", cond * (when_true - when_false.clone()) + when_false - out),
+            ])
+        });
+
+        meta.create_gate("mux", |meta| {
+            let mux_selector = meta.query_selector(mux_selector);
+            let value = meta.query_advice(advice[0], Rotation::cur());
+            let e = meta.query_advice(advice[1], Rotation::cur());
+            let [sum_cur, idx_cur, out_cur] = [2, 3, 4].map(|i| meta.query_advice(advice[i], Rotation::cur()));
+            let [sum_next, idx_next, out_next] = [2, 3, 4].map(|i| meta.query_advice(advice[i], Rotation::next()));
+            let position = meta.query_fixed(position);
+
+            Constraints::with_selector(mux_selector, [
+                ("e is boolean", e.clone() * (one.clone() - e.clone())),
+                ("sum", sum_cur + e.clone() - sum_next),
+                ("idx", idx_cur + position * e.clone() - idx_next),
+                ("out", out_cur + e * value - out_next),
+            ])
+        });
+
+        SelectConfig {
+            advice,
+            position,
+            select_selector,
+            mux_selector,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns `when_true` if `cond` is 1 and `when_false` if it is 0, constraining `cond`
+    /// to be boolean.
+    pub fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: AssignedCell<F, F>,
+        when_true: AssignedCell<F, F>,
+        when_false: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                config.select_selector.enable(&mut region, 0)?;
+
+                cond.copy_advice(|| "cond", &mut region, config.advice[0], 0)?;
+                when_true.copy_advice(|| "when true", &mut region, config.advice[1], 0)?;
+                when_false.copy_advice(|| "when false", &mut region, config.advice[2], 0)?;
+
+                let out = cond.value().zip(when_true.value()).zip(when_false.value())
+                    .map(|((cond, t), f)| *cond * (*t - *f) + *f);
+
+                region.assign_advice(|| "out", config.advice[3], 0, || out)
+            },
+        )
+    }
+
+    /// Returns `[b, a]` if `cond` is 1 and `[a, b]` if it is 0.
+    pub fn conditional_swap(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: AssignedCell<F, F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<[AssignedCell<F, F>; 2], Error> {
+        let first = self.select(layouter.namespace(|| "first"), cond.clone(), b.clone(), a.clone())?;
+        let second = self.select(layouter.namespace(|| "second"), cond, a, b)?;
+
+        Ok([first, second])
+    }
+
+    /// Returns `values[index]`; the proof fails for an index outside [0, values.len()).
+    pub fn mux(
+        &self,
+        mut layouter: impl Layouter<F>,
+        index: AssignedCell<F, F>,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!values.is_empty());
+
+        let config = self.config();
+
+        let [value_column, e_column, sum_column, idx_column, out_column] = config.advice;
+
+        layouter.assign_region(
+            || "mux",
+            |mut region| {
+                let mut sum = region.assign_advice_from_constant(|| "sum_0", sum_column, 0, F::ZERO)?;
+                let mut idx = region.assign_advice_from_constant(|| "idx_0", idx_column, 0, F::ZERO)?;
+                let mut out = region.assign_advice_from_constant(|| "out_0", out_column, 0, F::ZERO)?;
+
+                for (i, value) in values.iter().enumerate() {
+                    config.mux_selector.enable(&mut region, i)?;
+
+                    let position = F::from(i as u64);
+
+                    region.assign_fixed(|| "position", config.position, i, || Value::known(position))?;
+                    value.copy_advice(|| "value", &mut region, value_column, i)?;
+
+                    let e = index.value().map(|index| if *index == position { F::ONE } else { F::ZERO });
+
+                    region.assign_advice(|| "e", e_column, i, || e)?;
+
+                    let next_sum = sum.value().copied() + e;
+                    let next_idx = idx.value().copied() + e.map(|e| e * position);
+                    let next_out = out.value().copied() + e * value.value();
+
+                    sum = region.assign_advice(|| "sum", sum_column, i + 1, || next_sum)?;
+                    idx = region.assign_advice(|| "idx", idx_column, i + 1, || next_idx)?;
+                    out = region.assign_advice(|| "out", out_column, i + 1, || next_out)?;
+                }
+
+                region.constrain_constant(sum.cell(), F::ONE)?;
+                region.constrain_equal(idx.cell(), index.cell())?;
+
+                Ok(out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    struct SelectCircuit<F: PrimeField> {
+        cond: Value<F>,
+        index: Value<F>,
+        values: Vec<Value<F>>,
+    }
+
+    impl<F: PrimeField> Circuit<F> for SelectCircuit<F> {
+        type Config = (SelectConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                cond: Value::unknown(),
+                index: Value::unknown(),
+                values: vec![Value::unknown(); self.values.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 5].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            (SelectChip::configure(meta, advice), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            let (cond, index, values) = layouter.assign_region(|| "assign values", |mut region| {
+                let cond = region.assign_advice(|| "cond", config.advice[0], 0, || self.cond)?;
+                let index = region.assign_advice(|| "index", config.advice[1], 0, || self.index)?;
+
+                let values = self.values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| region.assign_advice(|| "value", config.advice[2], i, || *value))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok((cond, index, values))
+            })?;
+
+            let chip = SelectChip::construct(config);
+
+            // Expose select(cond, v_0, v_1), conditional_swap(cond, v_0, v_1) and mux(index, values).
+            let selected = chip.select(layouter.namespace(|| "select"), cond.clone(), values[0].clone(), values[1].clone())?;
+            let [first, second] = chip.conditional_swap(layouter.namespace(|| "swap"), cond, values[0].clone(), values[1].clone())?;
+            let muxed = chip.mux(layouter.namespace(|| "mux"), index, &values)?;
+
+            for (row, cell) in [selected, first, second, muxed].iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run(cond: u64, index: u64, values: &[u64], expected: [u64; 4]) -> bool {
+        let circuit = SelectCircuit {
+            cond: Value::known(Fp::from(cond)),
+            index: Value::known(Fp::from(index)),
+            values: values.iter().map(|&value| Value::known(Fp::from(value))).collect(),
+        };
+
+        let public_inputs = vec![expected.iter().map(|&value| Fp::from(value)).collect()];
+
+        MockProver::run(5, &circuit, public_inputs).unwrap().verify().is_ok()
+    }
+
+    #[test]
+    fn test_select_and_swap() {
+        assert!(run(1, 0, &[10, 20], [10, 20, 10, 10]));
+        assert!(run(0, 0, &[10, 20], [20, 10, 20, 10]));
+
+        assert!(!run(1, 0, &[10, 20], [20, 20, 10, 10]));
+        assert!(!run(0, 0, &[10, 20], [20, 20, 10, 10]));
+
+        // A non-boolean condition is rejected, even though 2 * (t - f) + f is well defined.
+        assert!(!run(2, 0, &[10, 20], [0, 30, 0, 10]));
+    }
+
+    #[test]
+    fn test_mux() {
+        let values = [5, 6, 7, 8, 9];
+
+        for index in 0..5 {
+            let expected = values[index as usize];
+
+            assert!(run(1, index, &values, [5, 6, 5, expected]));
+            assert!(!run(1, index, &values, [5, 6, 5, expected + 1]));
+        }
+
+        assert!(!run(1, 5, &values, [5, 6, 5, 0]));
+    }
+}
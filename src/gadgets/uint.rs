@@ -0,0 +1,527 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+
+/// Unsigned integer arithmetic on BITS-bit values, with carries and overflow made
+/// explicit instead of wrapping mod p.
+///
+/// ```text
+///  s_add:  a + b = out + 2^BITS * carry
+///  s_sub:  a - b + 2^BITS * carry = out
+///  s_mul:  a * b = out + 2^BITS * hi
+/// ```
+///
+/// ```text
+///  |  a  |  b  |  out  | carry / hi | s_add | s_sub | s_mul |
+///  ----------------------------------------------------------
+///  |  a  |  b  |  out  |     c      |   .   |   .   |   .   |
+/// ```
+///
+/// `out` (and `hi` for products) are range-checked to BITS bits with the decomposition
+/// gadget, while `carry` is constrained to be boolean. Together with BITS-bit inputs
+/// this makes every result the unique integer one, as long as 2 * BITS < F::NUM_BITS.
+/// Inputs must already be known to fit in BITS bits, e.g. via [`UIntChip::load_private`].
+#[derive(Clone, Debug)]
+pub struct UIntConfig<F: PrimeField, const BITS: usize> {
+    advice: [Column<Advice>; 4],
+    s_add: Selector,
+    s_sub: Selector,
+    s_mul: Selector,
+    decompose: DecomposeConfig<F>,
+}
+
+impl<F: PrimeField, const BITS: usize> UIntConfig<F, BITS> {
+    pub fn decompose(&self) -> &DecomposeConfig<F> {
+        &self.decompose
+    }
+}
+
+/// A BITS-bit result together with its carry, borrow or high word.
+#[derive(Clone, Debug)]
+pub struct Carried<F: PrimeField> {
+    pub value: AssignedCell<F, F>,
+    /// The carry out of an addition or the borrow of a subtraction.
+    pub carry: AssignedCell<F, F>,
+}
+
+/// The double-width product of two BITS-bit integers, `lo + 2^BITS * hi`.
+#[derive(Clone, Debug)]
+pub struct WideProduct<F: PrimeField> {
+    pub lo: AssignedCell<F, F>,
+    pub hi: AssignedCell<F, F>,
+}
+
+pub struct UIntChip<F: PrimeField, const BITS: usize> {
+    config: UIntConfig<F, BITS>,
+    _marker: PhantomData<F>,
+}
+
+pub type U32Chip<F> = UIntChip<F, 32>;
+pub type U64Chip<F> = UIntChip<F, 64>;
+
+impl<F: PrimeField, const BITS: usize> Chip<F> for UIntChip<F, BITS> {
+    type Config = UIntConfig<F, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits, const BITS: usize> UIntChip<F, BITS> {
+    pub fn construct(config: UIntConfig<F, BITS>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chip on `[a, b, out, carry]`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        decompose: DecomposeConfig<F>,
+    ) -> UIntConfig<F, BITS> {
+        assert!(BITS > 0 && BITS <= 64 && 2 * BITS < F::NUM_BITS as usize);
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let s_add = meta.selector();
+        let s_sub = meta.selector();
+        let s_mul = meta.selector();
+
+        let two_pow = Expression::Constant(F::from_u128(1 << BITS));
+
+        meta.create_gate("add with carry", |meta| {
+            let s_add = meta.query_selector(s_add);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[2], Rotation::cur());
+            let carry = meta.query_advice(advice[3], Rotation::cur());
+
+            Constraints::with_selector(s_add, [
+                ("a + b = out + 2^BITS * carry", a + b - out - two_pow.clone() * carry.clone()),
+                ("carry is boolean", carry.clone() * (Expression::Constant(F::ONE) - carry)),
+            ])
+        });
+
+        meta.create_gate("sub with borrow", |meta| {
+            let s_sub = meta.query_selector(s_sub);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[2], Rotation::cur());
+            let borrow = meta.query_advice(advice[3], Rotation::cur());
+
+            Constraints::with_selector(s_sub, [
+                ("a - b + 2^BITS * borrow = out", a - b + two_pow.clone() * borrow.clone() - out),
+                ("borrow is boolean", borrow.clone() * (Expression::Constant(F::ONE) - borrow)),
+            ])
+        });
+
+        meta.create_gate("wide mul", |meta| {
+            let s_mul = meta.query_selector(s_mul);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let lo = meta.query_advice(advice[2], Rotation::cur());
+            let hi = meta.query_advice(advice[3], Rotation::cur());
+
+            Constraints::with_selector(s_mul, [("a * b = lo + 2^BITS * hi", a * b - lo - two_pow.clone() * hi)])
+        });
+
+        UIntConfig {
+            advice,
+            s_add,
+            s_sub,
+            s_mul,
+            decompose,
+        }
+    }
+
+    fn range_check(&self, layouter: impl Layouter<F>, value: AssignedCell<F, F>) -> Result<(), Error> {
+        let decompose_chip = DecomposeChip::construct(self.config.decompose.clone());
+
+        decompose_chip.assign(layouter, value, BITS, true).map(|_| ())
+    }
+
+    /// Witnesses `value` and constrains it to BITS bits.
+    pub fn load_private(&self, mut layouter: impl Layouter<F>, value: Value<F>) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let cell = layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", config.advice[0], 0, || value),
+        )?;
+
+        self.range_check(layouter.namespace(|| "range check"), cell.clone())?;
+
+        Ok(cell)
+    }
+
+    // Assigns one row of `selector` and range-checks `out`, returning `out` and the fourth
+    // column. `op` maps the integer inputs to `(out, carry)`. The inputs are masked to BITS
+    // bits first, so that an input failing its range check makes the proof fail rather
+    // than overflowing `op`.
+    fn assign_op(
+        &self,
+        mut layouter: impl Layouter<F>,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        op: impl Fn(u128, u128) -> (u128, u128),
+    ) -> Result<Carried<F>, Error> {
+        let config = self.config();
+
+        let (value, carry) = layouter.assign_region(
+            || "uint op",
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                let mask = u128::MAX >> (128 - BITS);
+
+                let result = a.value().zip(b.value()).map(|(a, b)| {
+                    op(DecomposeChip::to_u128(a) & mask, DecomposeChip::to_u128(b) & mask)
+                });
+
+                let value = region.assign_advice(|| "out", config.advice[2], 0, || result.map(|(out, _)| F::from_u128(out)))?;
+                let carry = region.assign_advice(|| "carry", config.advice[3], 0, || result.map(|(_, carry)| F::from_u128(carry)))?;
+
+                Ok((value, carry))
+            },
+        )?;
+
+        self.range_check(layouter.namespace(|| "range check out"), value.clone())?;
+
+        Ok(Carried { value, carry })
+    }
+
+    /// Returns `a + b mod 2^BITS` and the carry out of the addition.
+    pub fn add_with_carry(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<Carried<F>, Error> {
+        self.assign_op(layouter, self.config.s_add, a, b, |a, b| {
+            let sum = a + b;
+
+            (sum & ((1 << BITS) - 1), sum >> BITS)
+        })
+    }
+
+    /// Returns `a - b mod 2^BITS` and the borrow, which is 1 iff `a < b`.
+    pub fn sub_with_borrow(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<Carried<F>, Error> {
+        self.assign_op(layouter, self.config.s_sub, a, b, |a, b| {
+            let borrow = (a < b) as u128;
+
+            (a + (borrow << BITS) - b, borrow)
+        })
+    }
+
+    /// Returns `a + b`, constraining it not to overflow BITS bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Synthesis` if the sum is known to overflow, rather than assigning a
+    /// carry that the constraint below could never accept.
+    pub fn checked_add(&self, mut layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        let sum = self.add_with_carry(layouter.namespace(|| "add with carry"), a, b)?;
+
+        sum.carry.value().error_if_known_and(|carry| **carry == F::ONE)?;
+
+        layouter.assign_region(
+            || "no overflow",
+            |mut region| region.constrain_constant(sum.carry.cell(), F::ZERO),
+        )?;
+
+        Ok(sum.value)
+    }
+
+    /// Returns `a + b mod 2^BITS`.
+    pub fn wrapping_add(&self, layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<AssignedCell<F, F>, Error> {
+        self.add_with_carry(layouter, a, b).map(|sum| sum.value)
+    }
+
+    /// Returns the full 2 * BITS-bit product of `a` and `b` as two BITS-bit words.
+    pub fn mul_wide(&self, mut layouter: impl Layouter<F>, a: &AssignedCell<F, F>, b: &AssignedCell<F, F>) -> Result<WideProduct<F>, Error> {
+        let product = self.assign_op(layouter.namespace(|| "mul"), self.config.s_mul, a, b, |a, b| {
+            let product = a * b;
+
+            (product & ((1 << BITS) - 1), product >> BITS)
+        })?;
+
+        self.range_check(layouter.namespace(|| "range check hi"), product.carry.clone())?;
+
+        Ok(WideProduct {
+            lo: product.value,
+            hi: product.carry,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::arithmetic::Field;
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+    use super::*;
+
+    fn configure<F: PrimeField + PrimeFieldBits, const BITS: usize>(
+        meta: &mut ConstraintSystem<F>,
+    ) -> (UIntConfig<F, BITS>, Column<Instance>) {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let c_i_bits = meta.advice_column();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(instance);
+
+        let lookup_table = RangeCheckTable::configure(meta, 1 << 4);
+        let decompose = DecomposeChip::configure(meta, advice[2], advice[3], c_i_bits, lookup_table, 4);
+
+        (UIntChip::configure(meta, advice, decompose), instance)
+    }
+
+    fn verify(circuit: &impl Circuit<Fp>, expected: &[u64]) -> bool {
+        let public_inputs = vec![expected.iter().map(|&value| Fp::from(value)).collect()];
+
+        MockProver::run(7, circuit, public_inputs).unwrap().verify().is_ok()
+    }
+
+    /// The operation applied to the two loaded inputs.
+    #[derive(Clone, Copy)]
+    enum Op {
+        AddWithCarry,
+        SubWithBorrow,
+        CheckedAdd,
+        WrappingAdd,
+        MulWide,
+    }
+
+    struct UIntCircuit<F: PrimeField, const BITS: usize> {
+        op: Op,
+        a: Value<F>,
+        b: Value<F>,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits, const BITS: usize> Circuit<F> for UIntCircuit<F, BITS> {
+        type Config = (UIntConfig<F, BITS>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                op: self.op,
+                a: Value::unknown(),
+                b: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(&self, (config, instance): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.decompose().lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let chip = UIntChip::construct(config);
+
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
+
+            let outputs = match self.op {
+                Op::AddWithCarry => {
+                    let result = chip.add_with_carry(layouter.namespace(|| "add with carry"), &a, &b)?;
+
+                    vec![result.value, result.carry]
+                }
+                Op::SubWithBorrow => {
+                    let result = chip.sub_with_borrow(layouter.namespace(|| "sub with borrow"), &a, &b)?;
+
+                    vec![result.value, result.carry]
+                }
+                Op::CheckedAdd => vec![chip.checked_add(layouter.namespace(|| "checked add"), &a, &b)?],
+                Op::WrappingAdd => vec![chip.wrapping_add(layouter.namespace(|| "wrapping add"), &a, &b)?],
+                Op::MulWide => {
+                    let result = chip.mul_wide(layouter.namespace(|| "mul wide"), &a, &b)?;
+
+                    vec![result.lo, result.hi]
+                }
+            };
+
+            for (row, output) in outputs.iter().enumerate() {
+                layouter.constrain_instance(output.cell(), instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn circuit<const BITS: usize>(op: Op, a: u64, b: u64) -> UIntCircuit<Fp, BITS> {
+        UIntCircuit {
+            op,
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        }
+    }
+
+    /// Lays out an `add with carry` row by hand with the given `out` and `carry`, range-checks
+    /// `out` as the chip does, and exposes both so that only the chip's own constraints can
+    /// reject them.
+    #[derive(Default)]
+    struct ForgedAddCircuit<F: PrimeField, const BITS: usize> {
+        a: Value<F>,
+        b: Value<F>,
+        out: Value<F>,
+        carry: Value<F>,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits, const BITS: usize> Circuit<F> for ForgedAddCircuit<F, BITS> {
+        type Config = (UIntConfig<F, BITS>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(&self, (config, instance): Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.decompose().lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let chip = UIntChip::construct(config.clone());
+
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
+
+            let (out, carry) = layouter.assign_region(
+                || "forged add",
+                |mut region| {
+                    config.s_add.enable(&mut region, 0)?;
+
+                    a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                    b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                    let out = region.assign_advice(|| "out", config.advice[2], 0, || self.out)?;
+                    let carry = region.assign_advice(|| "carry", config.advice[3], 0, || self.carry)?;
+
+                    Ok((out, carry))
+                },
+            )?;
+
+            chip.range_check(layouter.namespace(|| "range check out"), out.clone())?;
+
+            layouter.constrain_instance(out.cell(), instance, 0)?;
+            layouter.constrain_instance(carry.cell(), instance, 1)
+        }
+    }
+
+    #[test]
+    fn test_add_with_carry() {
+        for (a, b, expected) in [(100, 55, [155, 0]), (200, 100, [44, 1]), (255, 255, [254, 1])] {
+            assert!(verify(&circuit::<8>(Op::AddWithCarry, a, b), &expected));
+        }
+
+        assert!(verify(&circuit::<64>(Op::AddWithCarry, u64::MAX, 2), &[1, 1]));
+
+        // Inputs must fit in BITS bits.
+        assert!(!verify(&circuit::<8>(Op::AddWithCarry, 256, 0), &[0, 1]));
+    }
+
+    #[test]
+    fn test_forged_carry_rejected() {
+        let forged = |out: Fp, carry: Fp| {
+            let circuit = ForgedAddCircuit::<Fp, 8> {
+                a: Value::known(Fp::from(200)),
+                b: Value::known(Fp::from(100)),
+                out: Value::known(out),
+                carry: Value::known(carry),
+            };
+
+            MockProver::run(7, &circuit, vec![vec![out, carry]]).unwrap().verify().is_ok()
+        };
+
+        assert!(forged(Fp::from(44), Fp::ONE));
+
+        // A dropped carry breaks the addition.
+        assert!(!forged(Fp::from(44), Fp::ZERO));
+        // Keeping the carry in `out` fails its range check.
+        assert!(!forged(Fp::from(300), Fp::ZERO));
+        // A carry of 2 with a negative `out` fails booleanity.
+        assert!(!forged(Fp::from(300) - Fp::from(512), Fp::from(2)));
+    }
+
+    #[test]
+    fn test_sub_with_borrow() {
+        for (a, b, expected) in [(100, 55, [45, 0]), (55, 100, [211, 1])] {
+            assert!(verify(&circuit::<8>(Op::SubWithBorrow, a, b), &expected));
+        }
+
+        assert!(verify(&circuit::<32>(Op::SubWithBorrow, 0, 1), &[u32::MAX as u64, 1]));
+
+        assert!(!verify(&circuit::<8>(Op::SubWithBorrow, 55, 100), &[211, 0]));
+    }
+
+    #[test]
+    fn test_checked_add() {
+        assert!(verify(&circuit::<8>(Op::CheckedAdd, 200, 55), &[255]));
+        assert!(verify(&circuit::<32>(Op::CheckedAdd, u32::MAX as u64 - 1, 1), &[u32::MAX as u64]));
+
+        // An overflow is reported at synthesis.
+        let circuit = circuit::<8>(Op::CheckedAdd, 200, 56);
+
+        assert!(matches!(MockProver::run(7, &circuit, vec![vec![Fp::ZERO]]), Err(Error::Synthesis)));
+    }
+
+    #[test]
+    fn test_wrapping_add() {
+        assert!(verify(&circuit::<8>(Op::WrappingAdd, 200, 56), &[0]));
+        assert!(!verify(&circuit::<8>(Op::WrappingAdd, 200, 56), &[256]));
+
+        assert!(verify(&circuit::<32>(Op::WrappingAdd, u32::MAX as u64, 10), &[9]));
+    }
+
+    #[test]
+    fn test_mul_wide() {
+        for (a, b, expected) in [(255, 255, [1, 254]), (12, 10, [120, 0])] {
+            assert!(verify(&circuit::<8>(Op::MulWide, a, b), &expected));
+        }
+
+        let (a, b) = (u64::MAX, u64::MAX - 5);
+        let product = a as u128 * b as u128;
+
+        assert!(verify(&circuit::<64>(Op::MulWide, a, b), &[product as u64, (product >> 64) as u64]));
+
+        // lo + 2^8 * hi still equals the product, but lo does not fit in 8 bits.
+        assert!(!verify(&circuit::<8>(Op::MulWide, 255, 255), &[257, 253]));
+    }
+
+    #[test]
+    fn test_out_of_range_input() {
+        // Inputs wider than 64 bits would overflow the u128 witness computation; they must
+        // fail verification instead.
+        for (op, a, b) in [(Op::MulWide, 1 << 70, 1 << 70), (Op::SubWithBorrow, u128::MAX - 1, u128::MAX)] {
+            let circuit = UIntCircuit::<Fp, 64> {
+                op,
+                a: Value::known(Fp::from_u128(a)),
+                b: Value::known(Fp::from_u128(b)),
+            };
+
+            assert!(!verify(&circuit, &[0, 0]));
+        }
+    }
+}
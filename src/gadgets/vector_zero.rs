@@ -0,0 +1,265 @@
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter};
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::gadgets::is_zero::{Instructions, IsZeroChip, IsZeroConfig};
+
+/// Zero tests over a vector of cells.
+///
+/// Each value gets a row with its `IsZeroChip` inverse and the flag `z_i = [v_i = 0]`,
+/// and three accumulators run down the rows:
+///
+/// ```text
+///  count_0 = 0,   count_{i+1} = count_i + z_i
+///  all_0   = 1,   all_{i+1}   = all_i * z_i
+///  any_0   = 0,   any_{i+1}   = any_i + z_i - any_i * z_i
+/// ```
+///
+/// ```text
+///  |  value  | value_inv |  z  |  count  |  all  |  any  | q_zero |
+///  ---------------------------------------------------------------
+///  |   v_0   | v_0^{-1}  | z_0 |    0    |   1   |   0   |   1    |
+///  |   ...   |    ...    | ... |   ...   |  ...  |  ...  |  ...   |
+///  | v_{n-1} |    ...    | ... |   ...   |  ...  |  ...  |   1    |
+///  |         |           |     | count_n | all_n | any_n |   0    |
+/// ```
+#[derive(Clone, Debug)]
+pub struct VectorZeroConfig<F: Field> {
+    advice: [Column<Advice>; 6],
+    selector: Selector,
+    is_zero: IsZeroConfig<F>,
+}
+
+/// The accumulators after the last row, as returned by [`VectorZeroChip::summarize`].
+#[derive(Clone, Debug)]
+pub struct ZeroSummary<F: Field> {
+    /// The number of zero values.
+    pub count: AssignedCell<F, F>,
+    /// 1 iff every value is zero.
+    pub all: AssignedCell<F, F>,
+    /// 1 iff at least one value is zero.
+    pub any: AssignedCell<F, F>,
+}
+
+pub struct VectorZeroChip<F: Field> {
+    config: VectorZeroConfig<F>,
+}
+
+impl<F: Field> Chip<F> for VectorZeroChip<F> {
+    type Config = VectorZeroConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> VectorZeroChip<F> {
+    pub fn construct(config: VectorZeroConfig<F>) -> Self {
+        VectorZeroChip { config }
+    }
+
+    /// Configures the chip on `[value, value_inv, z, count, all, any]`.
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 6]) -> VectorZeroConfig<F> {
+        let [value, value_inv, z, count, all, any] = advice;
+
+        meta.enable_equality(value);
+        meta.enable_equality(count);
+        meta.enable_equality(all);
+        meta.enable_equality(any);
+
+        let fixed_column = meta.fixed_column();
+
+        meta.enable_constant(fixed_column);
+
+        let selector = meta.selector();
+
+        let is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            value_inv,
+        );
+
+        meta.create_gate("vector zero", |meta| {
+            let selector = meta.query_selector(selector);
+            let z = meta.query_advice(z, Rotation::cur());
+            let count_cur = meta.query_advice(count, Rotation::cur());
+            let count_next = meta.query_advice(count, Rotation::next());
+            let all_cur = meta.query_advice(all, Rotation::cur());
+            let all_next = meta.query_advice(all, Rotation::next());
+            let any_cur = meta.query_advice(any, Rotation::cur());
+            let any_next = meta.query_advice(any, Rotation::next());
+
+            Constraints::with_selector(selector, [
+                ("z = is_zero(value)", z.clone() - is_zero.expr()),
+                ("count", count_cur + z.clone() - count_next),
+                ("all", all_cur * z.clone() - all_next),
+                ("any", any_cur.clone() + z.clone() - any_cur * z - any_next),
+            ])
+        });
+
+        VectorZeroConfig {
+            advice,
+            selector,
+            is_zero,
+        }
+    }
+
+    /// Lays out `values` once and returns all three accumulators, for callers that need
+    /// more than one of `all_zero`, `any_zero` and `count_zero`.
+    pub fn summarize(&self, mut layouter: impl Layouter<F>, values: &[AssignedCell<F, F>]) -> Result<ZeroSummary<F>, Error> {
+        let config = self.config();
+        let is_zero_chip = IsZeroChip::construct(config.is_zero.clone());
+
+        let [value_column, _, z_column, count_column, all_column, any_column] = config.advice;
+
+        layouter.assign_region(
+            || "vector zero",
+            |mut region| {
+                let mut count = region.assign_advice_from_constant(|| "count_0", count_column, 0, F::ZERO)?;
+                let mut all = region.assign_advice_from_constant(|| "all_0", all_column, 0, F::ONE)?;
+                let mut any = region.assign_advice_from_constant(|| "any_0", any_column, 0, F::ZERO)?;
+
+                for (i, value) in values.iter().enumerate() {
+                    config.selector.enable(&mut region, i)?;
+
+                    value.copy_advice(|| "value", &mut region, value_column, i)?;
+                    is_zero_chip.assign(&mut region, value.value().copied(), i)?;
+
+                    let z = value.value().map(|value| if value.is_zero_vartime() { F::ONE } else { F::ZERO });
+
+                    region.assign_advice(|| "z", z_column, i, || z)?;
+
+                    let next_count = count.value().copied() + z;
+                    let next_all = all.value().copied() * z;
+                    let next_any = any.value().copied().zip(z).map(|(any, z)| any + z - any * z);
+
+                    count = region.assign_advice(|| "count", count_column, i + 1, || next_count)?;
+                    all = region.assign_advice(|| "all", all_column, i + 1, || next_all)?;
+                    any = region.assign_advice(|| "any", any_column, i + 1, || next_any)?;
+                }
+
+                Ok(ZeroSummary { count, all, any })
+            },
+        )
+    }
+
+    /// Returns a boolean cell that is 1 iff every value is zero (1 for an empty slice).
+    pub fn all_zero(&self, layouter: impl Layouter<F>, values: &[AssignedCell<F, F>]) -> Result<AssignedCell<F, F>, Error> {
+        self.summarize(layouter, values).map(|summary| summary.all)
+    }
+
+    /// Returns a boolean cell that is 1 iff at least one value is zero.
+    pub fn any_zero(&self, layouter: impl Layouter<F>, values: &[AssignedCell<F, F>]) -> Result<AssignedCell<F, F>, Error> {
+        self.summarize(layouter, values).map(|summary| summary.any)
+    }
+
+    /// Returns the number of zero values.
+    pub fn count_zero(&self, layouter: impl Layouter<F>, values: &[AssignedCell<F, F>]) -> Result<AssignedCell<F, F>, Error> {
+        self.summarize(layouter, values).map(|summary| summary.count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Value};
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    struct VectorZeroCircuit<F: Field> {
+        values: Vec<Value<F>>,
+        // Whether to take all outputs from a single `summarize` region.
+        summarize: bool,
+    }
+
+    impl<F: Field> Circuit<F> for VectorZeroCircuit<F> {
+        type Config = (VectorZeroConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                summarize: self.summarize,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 6].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            (VectorZeroChip::configure(meta, advice), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            let values = layouter.assign_region(|| "assign values", |mut region| {
+                self.values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| region.assign_advice(|| "value", config.advice[0], i, || *value))
+                    .collect::<Result<Vec<_>, _>>()
+            })?;
+
+            let chip = VectorZeroChip::construct(config);
+
+            let outputs = if self.summarize {
+                let summary = chip.summarize(layouter.namespace(|| "summarize"), &values)?;
+
+                [summary.all, summary.any, summary.count]
+            } else {
+                [
+                    chip.all_zero(layouter.namespace(|| "all zero"), &values)?,
+                    chip.any_zero(layouter.namespace(|| "any zero"), &values)?,
+                    chip.count_zero(layouter.namespace(|| "count zero"), &values)?,
+                ]
+            };
+
+            for (row, output) in outputs.iter().enumerate() {
+                layouter.constrain_instance(output.cell(), instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run(values: &[u64], expected: [u64; 3]) -> bool {
+        let public_inputs: Vec<_> = expected.iter().map(|&value| Fp::from(value)).collect();
+
+        let results = [false, true].map(|summarize| {
+            let circuit = VectorZeroCircuit {
+                values: values.iter().map(|&value| Value::known(Fp::from(value))).collect(),
+                summarize,
+            };
+
+            MockProver::run(5, &circuit, vec![public_inputs.clone()]).unwrap().verify().is_ok()
+        });
+
+        assert_eq!(results[0], results[1]);
+
+        results[0]
+    }
+
+    #[test]
+    fn test_vector_zero() {
+        assert!(run(&[0, 0, 0], [1, 1, 3]));
+        assert!(run(&[0, 3, 0, 9], [0, 1, 2]));
+        assert!(run(&[1, 2, 3], [0, 0, 0]));
+        assert!(run(&[], [1, 0, 0]));
+
+        assert!(!run(&[0, 0, 0], [0, 1, 3]));
+        assert!(!run(&[0, 3, 0, 9], [0, 0, 2]));
+        assert!(!run(&[0, 3, 0, 9], [0, 1, 1]));
+    }
+}
@@ -1,2 +1,2 @@
 pub mod gadgets;
-mod range_check;
+pub mod range_check;
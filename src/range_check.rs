@@ -1,4 +1,17 @@
 mod expression_check;
-mod lookup;
+pub mod lookup;
 pub mod lookup_with_bits;
-mod decompose;
+pub mod decompose;
+pub mod bits;
+pub mod canonical;
+pub mod short_range_check;
+pub mod interval;
+pub mod batch;
+pub mod bit_length;
+pub mod signed;
+pub mod comparison;
+pub mod min_max;
+pub mod divmod;
+mod chip;
+
+pub use chip::{RangeCheckChip, RangeCheckChipConfig, RangeCheckRecord, RangeCheckStrategy};
@@ -0,0 +1,299 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+/// This gadget range-constrains many values to n bits (n <= K) in a single region.
+///
+/// The values are laid out row by row across the advice columns, and every column is
+/// looked up in the K-bit table under one shared selector. For n < K each row of values
+/// is followed by a row of shifted values `value * 2^{K-n}`, as in the short range
+/// check; unused cells in the last row are filled with zeros.
+///
+/// ```text
+///  |   advice_0    |   advice_1    | ... |   shift   | q_lookup | q_shift |
+///  ------------------------------------------------------------------------
+///  |      v_0      |      v_1      | ... |  2^{K-n}  |    1     |    1    |
+///  | v_0 * 2^{K-n} | v_1 * 2^{K-n} | ... |           |    1     |    0    |
+///  |      v_C      |    v_{C+1}    | ... |  2^{K-n}  |    1     |    1    |
+///  |      ...      |      ...      | ... |    ...    |   ...    |   ...   |
+/// ```
+///
+/// Values wider than K bits do not fit a single lookup, so for n > K each value is
+/// instead decomposed on its own with the [`DecomposeChip`] running sum.
+#[derive(Clone, Debug)]
+pub struct BatchRangeCheckConfig<F: PrimeField> {
+    advice: Vec<Column<Advice>>,
+    shift: Column<Fixed>,
+    lookup_selector: Selector,
+    shift_selector: Selector,
+    decompose: DecomposeConfig<F>,
+}
+
+impl<F: PrimeField> BatchRangeCheckConfig<F> {
+    pub fn lookup_table(&self) -> &RangeCheckTable<F> {
+        self.decompose.lookup_table()
+    }
+}
+
+pub struct BatchRangeCheckChip<F: PrimeField> {
+    config: BatchRangeCheckConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for BatchRangeCheckChip<F> {
+    type Config = BatchRangeCheckConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> BatchRangeCheckChip<F> {
+    pub fn construct(config: BatchRangeCheckConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: &[Column<Advice>],
+        decompose: DecomposeConfig<F>,
+    ) -> BatchRangeCheckConfig<F> {
+        assert!(!advice.is_empty());
+
+        let lookup_table = decompose.lookup_table().value;
+
+        let lookup_selector = meta.complex_selector();
+        let shift_selector = meta.selector();
+
+        let shift = meta.fixed_column();
+
+        for &column in advice {
+            meta.enable_equality(column);
+
+            meta.lookup(|meta| {
+                let lookup_selector = meta.query_selector(lookup_selector);
+                let value = meta.query_advice(column, Rotation::cur());
+
+                vec![(lookup_selector * value, lookup_table)]
+            });
+        }
+
+        meta.create_gate("batch shift", |meta| {
+            let shift_selector = meta.query_selector(shift_selector);
+            let shift = meta.query_fixed(shift);
+
+            let constraints: Vec<_> = advice
+                .iter()
+                .map(|&column| {
+                    let value = meta.query_advice(column, Rotation::cur());
+                    let shifted = meta.query_advice(column, Rotation::next());
+
+                    value * shift.clone() - shifted
+                })
+                .collect();
+
+            Constraints::with_selector(shift_selector, constraints)
+        });
+
+        BatchRangeCheckConfig {
+            advice: advice.to_vec(),
+            shift,
+            lookup_selector,
+            shift_selector,
+            decompose,
+        }
+    }
+
+    /// Constrains every value to `num_bits` bits, returning the number of rows used.
+    ///
+    /// For `num_bits <= K` this is a single region of lookups; wider values fall back to
+    /// one running-sum region per value.
+    pub fn range_check_many(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>],
+        num_bits: usize,
+    ) -> Result<usize, Error> {
+        let config = self.config();
+
+        let lookup_bits = config.decompose.lookup_bits();
+
+        if num_bits > lookup_bits {
+            let decompose_chip = DecomposeChip::construct(config.decompose.clone());
+
+            return values.iter().enumerate().try_fold(0, |rows, (i, value)| {
+                let layouter = layouter.namespace(|| format!("decompose value {}", i));
+                let running_sum = decompose_chip.assign(layouter, value.clone(), num_bits, true)?;
+
+                Ok(rows + running_sum.zs.len())
+            });
+        }
+
+        let shifted = num_bits < lookup_bits;
+        let shift = F::from(1 << (lookup_bits - num_bits));
+
+        let rows_per_chunk = if shifted { 2 } else { 1 };
+        let num_rows = values.len().div_ceil(config.advice.len()) * rows_per_chunk;
+
+        layouter.assign_region(
+            || "batch range check",
+            |mut region| {
+                for (i, chunk) in values.chunks(config.advice.len()).enumerate() {
+                    let offset = i * rows_per_chunk;
+
+                    for row in offset..offset + rows_per_chunk {
+                        config.lookup_selector.enable(&mut region, row)?;
+                    }
+
+                    if shifted {
+                        config.shift_selector.enable(&mut region, offset)?;
+                        region.assign_fixed(|| "shift", config.shift, offset, || Value::known(shift))?;
+                    }
+
+                    for (j, &column) in config.advice.iter().enumerate() {
+                        let value = match chunk.get(j) {
+                            Some(value) => value.copy_advice(|| "value", &mut region, column, offset)?,
+                            None => region.assign_advice(|| "padding", column, offset, || Value::known(F::ZERO))?,
+                        };
+
+                        if shifted {
+                            region.assign_advice(
+                                || "shifted value",
+                                column,
+                                offset + 1,
+                                || value.value().map(|v| *v * shift),
+                            )?;
+                        }
+                    }
+                }
+
+                Ok(())
+            },
+        )?;
+
+        Ok(num_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::Circuit;
+
+    use super::*;
+
+    struct BatchCircuit<F: PrimeField> {
+        values: Vec<Value<F>>,
+        num_bits: usize,
+        expected_rows: usize,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for BatchCircuit<F> {
+        type Config = BatchRangeCheckConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                num_bits: self.num_bits,
+                expected_rows: self.expected_rows,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column(), meta.advice_column()];
+            let c_i_bits = meta.advice_column();
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << 4);
+            let decompose = DecomposeChip::configure(meta, advice[1], advice[2], c_i_bits, lookup_table, 4);
+
+            BatchRangeCheckChip::configure(meta, &advice, decompose)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let values = layouter.assign_region(|| "assign values", |mut region| {
+                self.values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| region.assign_advice(|| "value", config.advice[0], i, || *value))
+                    .collect::<Result<Vec<_>, _>>()
+            })?;
+
+            let chip = BatchRangeCheckChip::construct(config);
+
+            let rows = chip.range_check_many(layouter.namespace(|| "range check many"), &values, self.num_bits)?;
+
+            assert_eq!(rows, self.expected_rows);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_range_check_many() {
+        let values: Vec<_> = (0..100u64).map(|i| Value::known(Fp::from(i % 8))).collect();
+
+        for (num_bits, expected_rows) in [(4, 34), (3, 68)] {
+            let circuit = BatchCircuit { values: values.clone(), num_bits, expected_rows };
+
+            let prover = MockProver::run(8, &circuit, vec![]).unwrap();
+
+            prover.assert_satisfied();
+        }
+
+        let mut values = values;
+        values[57] = Value::known(Fp::from(8));
+
+        let circuit = BatchCircuit { values: values.clone(), num_bits: 3, expected_rows: 68 };
+
+        let prover = MockProver::run(8, &circuit, vec![]).unwrap();
+
+        assert!(prover.verify().is_err());
+
+        let circuit = BatchCircuit { values, num_bits: 4, expected_rows: 34 };
+
+        let prover = MockProver::run(8, &circuit, vec![]).unwrap();
+
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_range_check_many_wide() {
+        // 10 bits is wider than the 4-bit table: three chunks, so four rows per value.
+        let values: Vec<_> = [0, 1, 7, 512, 1023].map(|v| Value::known(Fp::from(v))).to_vec();
+
+        let circuit = BatchCircuit { values: values.clone(), num_bits: 10, expected_rows: 20 };
+
+        let prover = MockProver::run(8, &circuit, vec![]).unwrap();
+
+        prover.assert_satisfied();
+
+        let mut values = values;
+        values[3] = Value::known(Fp::from(1024));
+
+        let circuit = BatchCircuit { values, num_bits: 10, expected_rows: 20 };
+
+        let prover = MockProver::run(8, &circuit, vec![]).unwrap();
+
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::gadgets::is_zero::{Instructions, IsZeroChip, IsZeroConfig};
+use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+
+/// This gadget computes the minimal bit-length of an N-bit value, with `bit_length(0) = 0`.
+///
+/// The value is decomposed into K-bit chunks with the running sum, whose z_i is the
+/// value shifted right by iK bits. If c_j is the highest non-zero chunk, then exactly
+/// z_0, ..., z_j are non-zero and
+///
+/// ```text
+///  bit_length(value) = j * K + bits(c_j)
+/// ```
+///
+/// where bits(c_j) is the tag of c_j in the lookup table. With nz_i = [z_i != 0] we
+/// accumulate this as
+///
+/// ```text
+///  acc_0     = 0
+///  acc_{i+1} = acc_i + K * nz_{i+1} + nz_i * (1 - nz_{i+1}) * bits(c_i)
+/// ```
+///
+/// ```text
+///  |  z_i  | bits(c_i) |   z_inv   |  nz_i  |  acc_i  | q_nonzero | q_bit_length |
+///  ----------------------------------------------------------------------------------
+///  |  z_0  | bits(c_0) | z_0^{-1}  |  nz_0  |    0    |     1     |      1       |
+///  |  ...  |    ...    |    ...    |  ...   |   ...   |    ...    |     ...      |
+///  |  z_C  |           | z_C^{-1}  |  nz_C  |  acc_C  |     1     |      0       |
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitLengthConfig<F: PrimeField> {
+    z: Column<Advice>,
+    chunk_bits: Column<Advice>,
+    nonzero: Column<Advice>,
+    acc: Column<Advice>,
+    nonzero_selector: Selector,
+    bit_length_selector: Selector,
+    is_zero: IsZeroConfig<F>,
+    decompose: DecomposeConfig<F>,
+}
+
+pub struct BitLengthChip<F: PrimeField> {
+    config: BitLengthConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for BitLengthChip<F> {
+    type Config = BitLengthConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> BitLengthChip<F> {
+    pub fn construct(config: BitLengthConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        decompose: DecomposeConfig<F>,
+    ) -> BitLengthConfig<F> {
+        let [z, chunk_bits, z_inv, nonzero, acc] = advice;
+
+        meta.enable_equality(z);
+        meta.enable_equality(chunk_bits);
+        meta.enable_equality(acc);
+
+        let nonzero_selector = meta.selector();
+        let bit_length_selector = meta.selector();
+
+        let is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(nonzero_selector),
+            |meta| meta.query_advice(z, Rotation::cur()),
+            z_inv,
+        );
+
+        meta.create_gate("nonzero flag", |meta| {
+            let nonzero_selector = meta.query_selector(nonzero_selector);
+            let nonzero = meta.query_advice(nonzero, Rotation::cur());
+
+            Constraints::with_selector(nonzero_selector, [
+                ("nz = 1 - is_zero(z)", nonzero - (Expression::Constant(F::ONE) - is_zero.expr())),
+            ])
+        });
+
+        let lookup_bits = decompose.lookup_bits() as u64;
+
+        meta.create_gate("bit length", |meta| {
+            let bit_length_selector = meta.query_selector(bit_length_selector);
+            let chunk_bits = meta.query_advice(chunk_bits, Rotation::cur());
+            let nonzero_cur = meta.query_advice(nonzero, Rotation::cur());
+            let nonzero_next = meta.query_advice(nonzero, Rotation::next());
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+
+            let top_chunk = nonzero_cur * (Expression::Constant(F::ONE) - nonzero_next.clone());
+
+            Constraints::with_selector(bit_length_selector, [(
+                "accumulate bit length",
+                acc_cur + nonzero_next * Expression::Constant(F::from(lookup_bits)) + top_chunk * chunk_bits - acc_next,
+            )])
+        });
+
+        BitLengthConfig {
+            z,
+            chunk_bits,
+            nonzero,
+            acc,
+            nonzero_selector,
+            bit_length_selector,
+            is_zero,
+            decompose,
+        }
+    }
+
+    /// Returns the minimal bit-length of `value`, constraining `value` to `num_bits` bits.
+    pub fn bit_length(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let decompose_chip = DecomposeChip::construct(config.decompose.clone());
+        let running_sum = decompose_chip.assign(layouter.namespace(|| "decompose"), value, num_bits, true)?;
+
+        let is_zero_chip = IsZeroChip::construct(config.is_zero.clone());
+
+        let lookup_bits = F::from(config.decompose.lookup_bits() as u64);
+
+        layouter.assign_region(
+            || "bit length",
+            |mut region| {
+                let nonzero: Vec<_> = running_sum.zs
+                    .iter()
+                    .map(|z| z.value().map(|z| if z.is_zero_vartime() { F::ZERO } else { F::ONE }))
+                    .collect();
+
+                let mut acc = region.assign_advice_from_constant(|| "acc_0", config.acc, 0, F::ZERO)?;
+
+                for (i, z) in running_sum.zs.iter().enumerate() {
+                    config.nonzero_selector.enable(&mut region, i)?;
+
+                    z.copy_advice(|| "z_i", &mut region, config.z, i)?;
+                    is_zero_chip.assign(&mut region, z.value().copied(), i)?;
+                    region.assign_advice(|| "nz_i", config.nonzero, i, || nonzero[i])?;
+
+                    if let Some(chunk_bits) = running_sum.chunk_bits.get(i) {
+                        config.bit_length_selector.enable(&mut region, i)?;
+
+                        chunk_bits.copy_advice(|| "bits(c_i)", &mut region, config.chunk_bits, i)?;
+
+                        let top_chunk = nonzero[i] * nonzero[i + 1].map(|nz| F::ONE - nz);
+                        let next = acc.value().copied()
+                            + nonzero[i + 1].map(|nz| nz * lookup_bits)
+                            + top_chunk * chunk_bits.value();
+
+                        acc = region.assign_advice(|| "acc_i", config.acc, i + 1, || next)?;
+                    }
+                }
+
+                Ok(acc)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Value};
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+    use super::*;
+
+    struct BitLengthCircuit<F: PrimeField> {
+        value: Value<F>,
+        num_bits: usize,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for BitLengthCircuit<F> {
+        type Config = (BitLengthConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                num_bits: self.num_bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 5].map(|_| meta.advice_column());
+            let c_i_bits = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << 4);
+            let decompose = DecomposeChip::configure(meta, advice[0], advice[1], c_i_bits, lookup_table, 4);
+
+            (BitLengthChip::configure(meta, advice, decompose), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            config.decompose.lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let value = layouter.assign_region(|| "assign value", |mut region| {
+                region.assign_advice(|| "value", config.z, 0, || self.value)
+            })?;
+
+            let chip = BitLengthChip::construct(config);
+
+            let bit_length = chip.bit_length(layouter.namespace(|| "bit length"), value, self.num_bits)?;
+
+            layouter.constrain_instance(bit_length.cell(), instance, 0)
+        }
+    }
+
+    #[test]
+    fn test_bit_length() {
+        for (value, expected) in [(0, 0), (1, 1), (2, 2), (15, 4), (16, 5), (1000, 10), (65535, 16), ((1 << 19) + 5, 20)] {
+            let circuit = BitLengthCircuit {
+                value: Value::known(Fp::from(value)),
+                num_bits: 20,
+            };
+
+            let prover = MockProver::run(6, &circuit, vec![vec![Fp::from(expected)]]).unwrap();
+
+            prover.assert_satisfied();
+
+            let prover = MockProver::run(6, &circuit, vec![vec![Fp::from(expected + 1)]]).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+
+        let circuit = BitLengthCircuit {
+            value: Value::known(Fp::from(1 << 20)),
+            num_bits: 20,
+        };
+
+        let prover = MockProver::run(6, &circuit, vec![vec![Fp::from(21)]]).unwrap();
+
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,276 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::decompose::DecomposeChip;
+
+/// This gadget decomposes an N-bit value into boolean cells, and recomposes boolean
+/// cells into a value.
+///
+/// It is the running sum of [`DecomposeChip`] with K = 1, where each chunk is a single
+/// bit constrained by `b_i * (1 - b_i) = 0` instead of a table lookup:
+///
+/// ```text
+///  z_0 = value,   z_{i+1} = (z_i - b_i) / 2,   z_N = 0
+/// ```
+///
+/// ```text
+///  |  z  |    bit    | q_bits |
+///  ----------------------------
+///  | z_0 |    b_0    |   1    |
+///  | z_1 |    b_1    |   1    |
+///  | ... |    ...    |  ...   |
+///  | z_N |           |   0    |
+/// ```
+///
+/// Both directions use the same layout: `to_bits` copies in z_0 and witnesses the bits,
+/// while `from_bits` copies in the bits and witnesses the running sum.
+#[derive(Clone, Debug)]
+pub struct BitsConfig<F: PrimeField> {
+    z: Column<Advice>,
+    bit: Column<Advice>,
+    selector: Selector,
+    _marker: PhantomData<F>,
+}
+
+pub struct BitsChip<F: PrimeField> {
+    config: BitsConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for BitsChip<F> {
+    type Config = BitsConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> BitsChip<F> {
+    pub fn construct(config: BitsConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        z: Column<Advice>,
+        bit: Column<Advice>,
+    ) -> BitsConfig<F> {
+        let fixed_column = meta.fixed_column();
+
+        meta.enable_constant(fixed_column);
+
+        meta.enable_equality(z);
+        meta.enable_equality(bit);
+
+        let selector = meta.selector();
+
+        meta.create_gate("binary running sum", |meta| {
+            let selector = meta.query_selector(selector);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let bit = meta.query_advice(bit, Rotation::cur());
+
+            Constraints::with_selector(selector, [
+                ("b_i = z_i - 2 * z_{i+1}", z_cur - z_next * Expression::Constant(F::from(2)) - bit.clone()),
+                ("b_i is boolean", bit.clone() * (Expression::Constant(F::ONE) - bit)),
+            ])
+        });
+
+        BitsConfig {
+            z,
+            bit,
+            selector,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Decomposes `value` into `num_bits` little-endian boolean cells, constraining it to
+    /// `num_bits` bits.
+    pub fn to_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(num_bits > 0 && num_bits < F::NUM_BITS as usize);
+
+        let config = self.config();
+
+        let two_inv = F::from(2).invert().unwrap();
+
+        layouter.assign_region(
+            || "to bits",
+            |mut region| {
+                let mut z = value.copy_advice(|| "z_0", &mut region, config.z, 0)?;
+
+                let bits = value.value().map(|v| DecomposeChip::chunks(*v, num_bits, 1));
+
+                let mut bit_cells = vec![];
+
+                for i in 0..num_bits {
+                    config.selector.enable(&mut region, i)?;
+
+                    let bit = bits.as_ref().map(|bits| F::from(bits[i].0));
+
+                    bit_cells.push(region.assign_advice(|| "b_i", config.bit, i, || bit)?);
+
+                    let z_i = z.value().copied().zip(bit).map(|(z, bit)| (z - bit) * two_inv);
+
+                    z = region.assign_advice(|| "z_i", config.z, i + 1, || z_i)?;
+                }
+
+                region.constrain_constant(z.cell(), F::ZERO)?;
+
+                Ok(bit_cells)
+            },
+        )
+    }
+
+    /// Recomposes little-endian `bits` into a value, constraining each of them to be
+    /// boolean.
+    pub fn from_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!bits.is_empty() && bits.len() < F::NUM_BITS as usize);
+
+        let config = self.config();
+
+        layouter.assign_region(
+            || "from bits",
+            |mut region| {
+                let mut z = region.assign_advice_from_constant(|| "z_N", config.z, bits.len(), F::ZERO)?;
+
+                for (i, bit) in bits.iter().enumerate().rev() {
+                    config.selector.enable(&mut region, i)?;
+
+                    bit.copy_advice(|| "b_i", &mut region, config.bit, i)?;
+
+                    let z_i = z.value().copied().zip(bit.value()).map(|(z, bit)| z * F::from(2) + *bit);
+
+                    z = region.assign_advice(|| "z_i", config.z, i, || z_i)?;
+                }
+
+                Ok(z)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Value};
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use super::*;
+
+    struct BitsCircuit<F: PrimeField> {
+        value: Value<F>,
+        num_bits: usize,
+        // Bits recomposed with `from_bits`, which may be non-boolean.
+        bits: Vec<Value<F>>,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for BitsCircuit<F> {
+        type Config = (BitsConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                num_bits: self.num_bits,
+                bits: vec![Value::unknown(); self.bits.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let z = meta.advice_column();
+            let bit = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            (BitsChip::configure(meta, z, bit), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            let (value, bits) = layouter.assign_region(|| "assign values", |mut region| {
+                let value = region.assign_advice(|| "value", config.z, 0, || self.value)?;
+
+                let bits = self.bits
+                    .iter()
+                    .enumerate()
+                    .map(|(i, bit)| region.assign_advice(|| "bit", config.bit, i, || *bit))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok((value, bits))
+            })?;
+
+            let chip = BitsChip::construct(config);
+
+            // Expose the bits of `value`, followed by the recomposition of `bits`.
+            let value_bits = chip.to_bits(layouter.namespace(|| "to bits"), value, self.num_bits)?;
+            let recomposed = chip.from_bits(layouter.namespace(|| "from bits"), &bits)?;
+
+            for (row, cell) in value_bits.iter().chain(std::iter::once(&recomposed)).enumerate() {
+                layouter.constrain_instance(cell.cell(), instance, row)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run(value: u64, num_bits: usize, bits: &[u64], recomposed: u64) -> bool {
+        let circuit = BitsCircuit {
+            value: Value::known(Fp::from(value)),
+            num_bits,
+            bits: bits.iter().map(|&bit| Value::known(Fp::from(bit))).collect(),
+        };
+
+        let public_inputs = (0..num_bits)
+            .map(|i| Fp::from((value >> i) & 1))
+            .chain(std::iter::once(Fp::from(recomposed)))
+            .collect();
+
+        MockProver::run(6, &circuit, vec![public_inputs]).unwrap().verify().is_ok()
+    }
+
+    #[test]
+    fn test_to_bits() {
+        for (value, num_bits) in [(0, 1), (1, 1), (0b1011, 4), (0b1011, 8), ((1 << 20) - 1, 20)] {
+            assert!(run(value, num_bits, &[1], 1));
+        }
+
+        for (value, num_bits) in [(2, 1), (0b10000, 4), (1 << 20, 20)] {
+            assert!(!run(value, num_bits, &[1], 1));
+        }
+    }
+
+    #[test]
+    fn test_from_bits() {
+        assert!(run(0, 1, &[1, 1, 0, 1], 0b1011));
+        assert!(run(0, 1, &[0, 0, 0, 0, 0, 0, 0, 1], 128));
+
+        assert!(!run(0, 1, &[1, 1, 0, 1], 0b1010));
+        // 2 * 1 + 0 = 2 would recompose to the same value as [0, 1].
+        assert!(!run(0, 1, &[2, 0], 2));
+    }
+}
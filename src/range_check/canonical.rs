@@ -0,0 +1,360 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::decompose::{DecomposeChip, DecomposeConfig, RunningSum};
+
+/// This gadget decomposes a field element into the bits of its canonical integer
+/// representative, i.e. the unique integer `alpha < p` congruent to the element.
+///
+/// Write the modulus as p = 2^m + t where m = NUM_BITS - 1 and t has T bits
+/// (for both Pasta fields m = 254 and T = 126). The element is split as
+///
+/// ```text
+///  alpha = alpha_low + 2^T * alpha_high + 2^m * alpha_top
+/// ```
+///
+/// with alpha_low in T bits, alpha_high in m - T bits and alpha_top a single bit.
+/// This is the canonical representative iff it is below p, i.e. iff
+///
+/// ```text
+///  alpha_top = 1  =>  alpha_high = 0 and alpha_low < t.
+/// ```
+///
+/// The second condition is enforced by range-checking
+///
+/// ```text
+///  shifted = alpha_low + alpha_top * (2^T - t)
+/// ```
+///
+/// to T bits: when alpha_top = 0 this is just alpha_low again, and when alpha_top = 1
+/// it is below 2^T exactly when alpha_low < t. Since T < m, `shifted` never wraps
+/// around the modulus.
+///
+/// ```text
+///  | value | low | high | top | shifted | q_canonical |
+///  ----------------------------------------------------
+///  | alpha | a_l | a_h  | a_t |   s     |      1      |
+/// ```
+#[derive(Clone, Debug)]
+pub struct CanonicalConfig<F: PrimeField> {
+    value: Column<Advice>,
+    low: Column<Advice>,
+    high: Column<Advice>,
+    top: Column<Advice>,
+    shifted: Column<Advice>,
+    selector: Selector,
+    decompose: DecomposeConfig<F>,
+    low_bits: usize,
+}
+
+/// The canonical decomposition of a field element, as returned by [`CanonicalChip::assign`].
+///
+/// The windows are those of the two range checks, not of the whole element: `low` is
+/// windowed from bit 0 and `high` from bit T, so unless K divides T the windows of
+/// `high_running_sum` are not aligned with K-bit windows of alpha. The last window of
+/// each part may be shorter than K bits.
+#[derive(Clone, Debug)]
+pub struct CanonicalDecomposition<F: PrimeField> {
+    /// Bits 0..T of the value.
+    pub low: AssignedCell<F, F>,
+    /// Bits T..m of the value.
+    pub high: AssignedCell<F, F>,
+    /// Bit m of the value, i.e. bit 254 for the Pasta fields.
+    pub top: AssignedCell<F, F>,
+    /// The K-bit windows of `low`, least significant first, covering bits 0..T.
+    pub low_running_sum: RunningSum<F>,
+    /// The K-bit windows of `high`, least significant first, restarting at bit T: window
+    /// `i` covers bits T + i * K..T + (i + 1) * K of the value.
+    pub high_running_sum: RunningSum<F>,
+}
+
+pub struct CanonicalChip<F: PrimeField> {
+    config: CanonicalConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for CanonicalChip<F> {
+    type Config = CanonicalConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> CanonicalChip<F> {
+    pub fn construct(config: CanonicalConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Index m of the most significant bit of the modulus.
+    fn top_bit() -> usize {
+        F::NUM_BITS as usize - 1
+    }
+
+    /// `t = p - 2^m`, as a field element.
+    fn modulus_tail() -> F {
+        -F::from(2).pow_vartime([Self::top_bit() as u64])
+    }
+
+    fn bits_to_field(bits: &[bool]) -> F {
+        bits.iter().rev().fold(F::ZERO, |acc, bit| acc.double() + if *bit { F::ONE } else { F::ZERO })
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        decompose: DecomposeConfig<F>,
+    ) -> CanonicalConfig<F> {
+        let [value, low, high, top, shifted] = advice;
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let top_bit = Self::top_bit();
+        let tail = Self::modulus_tail();
+        let low_bits = tail.to_le_bits().iter().by_vals().rposition(|bit| bit).map_or(1, |i| i + 1);
+
+        assert!(low_bits < top_bit, "modulus must be of the form 2^m + t with t < 2^(m - 1)");
+
+        let selector = meta.selector();
+
+        meta.create_gate("canonical decomposition", |meta| {
+            let selector = meta.query_selector(selector);
+            let value = meta.query_advice(value, Rotation::cur());
+            let low = meta.query_advice(low, Rotation::cur());
+            let high = meta.query_advice(high, Rotation::cur());
+            let top = meta.query_advice(top, Rotation::cur());
+            let shifted = meta.query_advice(shifted, Rotation::cur());
+
+            let two_pow_low = F::from(2).pow_vartime([low_bits as u64]);
+            let two_pow_top = F::from(2).pow_vartime([top_bit as u64]);
+
+            let one = Expression::Constant(F::ONE);
+
+            Constraints::with_selector(selector, [
+                (
+                    "recompose",
+                    low.clone()
+                        + high.clone() * Expression::Constant(two_pow_low)
+                        + top.clone() * Expression::Constant(two_pow_top)
+                        - value,
+                ),
+                ("top is boolean", top.clone() * (one - top.clone())),
+                ("top => high = 0", top.clone() * high),
+                ("shifted", low + top * Expression::Constant(two_pow_low - tail) - shifted),
+            ])
+        });
+
+        CanonicalConfig {
+            value,
+            low,
+            high,
+            top,
+            shifted,
+            selector,
+            decompose,
+            low_bits,
+        }
+    }
+
+    /// Decomposes `value` and proves the decomposition is the canonical one.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+    ) -> Result<CanonicalDecomposition<F>, Error> {
+        let low_bits = self.config().low_bits;
+        let top_bit = Self::top_bit();
+
+        let split = value.value().map(|v| {
+            let bits: Vec<bool> = v.to_le_bits().iter().by_vals().collect();
+
+            [
+                Self::bits_to_field(&bits[..low_bits]),
+                Self::bits_to_field(&bits[low_bits..top_bit]),
+                if bits[top_bit] { F::ONE } else { F::ZERO },
+            ]
+        });
+
+        self.assign_split(layouter, value, split)
+    }
+
+    /// Witnesses `[low, high, top]` for `value` and constrains it to be the canonical split.
+    fn assign_split(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        split: Value<[F; 3]>,
+    ) -> Result<CanonicalDecomposition<F>, Error> {
+        let config = self.config();
+
+        let low_bits = config.low_bits;
+        let top_bit = Self::top_bit();
+        let tail = Self::modulus_tail();
+
+        let (low, high, top, shifted) = layouter.assign_region(
+            || "canonical decomposition",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                value.copy_advice(|| "value", &mut region, config.value, 0)?;
+
+                let low = region.assign_advice(|| "low", config.low, 0, || split.map(|split| split[0]))?;
+                let high = region.assign_advice(|| "high", config.high, 0, || split.map(|split| split[1]))?;
+                let top = region.assign_advice(|| "top", config.top, 0, || split.map(|split| split[2]))?;
+
+                let two_pow_low = F::from(2).pow_vartime([low_bits as u64]);
+                let shifted_value = low.value().copied() + top.value().map(|top| *top * (two_pow_low - tail));
+
+                let shifted = region.assign_advice(|| "shifted", config.shifted, 0, || shifted_value)?;
+
+                Ok((low, high, top, shifted))
+            },
+        )?;
+
+        let decompose_chip = DecomposeChip::construct(config.decompose.clone());
+
+        let low_running_sum = decompose_chip.assign(layouter.namespace(|| "range check low"), low.clone(), low_bits, true)?;
+        let high_running_sum =
+            decompose_chip.assign(layouter.namespace(|| "range check high"), high.clone(), top_bit - low_bits, true)?;
+        decompose_chip.assign(layouter.namespace(|| "range check shifted"), shifted, low_bits, true)?;
+
+        Ok(CanonicalDecomposition {
+            low,
+            high,
+            top,
+            low_running_sum,
+            high_running_sum,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::arithmetic::Field;
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::{Fp, Fq};
+    use halo2_proofs::plonk::Circuit;
+
+    use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct CanonicalCircuit<F: PrimeField> {
+        value: Value<F>,
+        /// A `[low, high, top]` split to witness instead of the honest one.
+        forged_split: Option<[F; 3]>,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for CanonicalCircuit<F> {
+        type Config = CanonicalConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                forged_split: self.forged_split,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 5].map(|_| meta.advice_column());
+            let c_i_bits = meta.advice_column();
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << 8);
+            let decompose = DecomposeChip::configure(meta, advice[0], advice[1], c_i_bits, lookup_table, 8);
+
+            CanonicalChip::configure(meta, advice, decompose)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.decompose.lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let value = layouter.assign_region(|| "assign value", |mut region| {
+                region.assign_advice(|| "value", config.value, 0, || self.value)
+            })?;
+
+            let chip = CanonicalChip::construct(config);
+
+            match self.forged_split {
+                Some(split) => chip.assign_split(layouter.namespace(|| "forged decomposition"), value, Value::known(split))?,
+                None => chip.assign(layouter.namespace(|| "canonical decomposition"), value)?,
+            };
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_canonical_decomposition() {
+        let two_pow_254 = Fp::from(2).pow_vartime([254]);
+
+        for value in [Fp::ZERO, Fp::ONE, Fp::from(u64::MAX), two_pow_254, -Fp::ONE, -Fp::from(12345)] {
+            let circuit = CanonicalCircuit { value: Value::known(value), forged_split: None };
+
+            let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+
+            prover.assert_satisfied();
+        }
+
+        for value in [Fq::ZERO, -Fq::ONE, Fq::from(2).pow_vartime([254]) - Fq::ONE] {
+            let circuit = CanonicalCircuit { value: Value::known(value), forged_split: None };
+
+            let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_non_canonical_decomposition_rejected() {
+        let two_pow_126 = Fp::from(2).pow_vartime([126]);
+        let two_pow_254 = Fp::from(2).pow_vartime([254]);
+        let tail = -two_pow_254;
+
+        let recompose = |[low, high, top]: [Fp; 3]| low + high * two_pow_126 + top * two_pow_254;
+
+        for split in [
+            // 5 + p, which is congruent to 5.
+            [Fp::from(5) + tail, Fp::ZERO, Fp::ONE],
+            // top = 1 with a nonzero high part.
+            [Fp::ZERO, Fp::ONE, Fp::ONE],
+            // top = 1 with low = t, i.e. p itself as an encoding of zero.
+            [tail, Fp::ZERO, Fp::ONE],
+        ] {
+            let circuit = CanonicalCircuit {
+                value: Value::known(recompose(split)),
+                forged_split: Some(split),
+            };
+
+            let prover = MockProver::run(9, &circuit, vec![]).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+
+        // The forged split goes through the same path an honest one does.
+        let honest = [Fp::from(5), Fp::ZERO, Fp::ZERO];
+        let circuit = CanonicalCircuit {
+            value: Value::known(recompose(honest)),
+            forged_split: Some(honest),
+        };
+
+        MockProver::run(9, &circuit, vec![]).unwrap().assert_satisfied();
+    }
+}
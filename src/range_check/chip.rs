@@ -0,0 +1,395 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+use crate::range_check::expression_check::ExpressionConfig;
+use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+/// The constraint used to prove `value < range`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeCheckStrategy {
+    /// Use the cheapest of the strategies below that supports the range.
+    Auto,
+    /// A product gate `(0 - value) * ... * (R - 1 - value)`, for ranges up to the
+    /// configured polynomial range R.
+    Polynomial,
+    /// A single lookup into the K-bit table, for ranges up to 2^K.
+    Lookup,
+    /// A running-sum decomposition into K-bit chunks, for any range.
+    Decompose,
+}
+
+/// Which strategy a range check compiled to, as recorded by [`RangeCheckChip`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeCheckRecord {
+    pub range: u128,
+    pub strategy: RangeCheckStrategy,
+}
+
+/// A range check for `value < range` that dispatches to one of the strategies above.
+///
+/// Every strategy natively proves `value < B` for some bound B (R, 2^K or 2^N). For a
+/// range below B we additionally check the shifted value `value + (B - range)`, which
+/// is below B exactly when `value < range`:
+///
+/// ```text
+///  |        value        |    shift    | q_shift | q_polynomial / q_lookup |
+///  -------------------------------------------------------------------------
+///  |        value        |  B - range  |    1    |            1            |
+///  | value + B - range   |             |    0    |            1            |
+/// ```
+///
+/// The decomposition strategy runs the same shift in its own region and decomposes
+/// both values into N bits with [`DecomposeChip`].
+#[derive(Clone, Debug)]
+pub struct RangeCheckChipConfig<F: PrimeField> {
+    value: Column<Advice>,
+    shift: Column<Fixed>,
+    shift_selector: Selector,
+    polynomial: ExpressionConfig<F>,
+    lookup_selector: Selector,
+    decompose: DecomposeConfig<F>,
+    polynomial_range: usize,
+}
+
+impl<F: PrimeField> RangeCheckChipConfig<F> {
+    pub fn lookup_table(&self) -> &RangeCheckTable<F> {
+        self.decompose.lookup_table()
+    }
+}
+
+pub struct RangeCheckChip<F: PrimeField> {
+    config: RangeCheckChipConfig<F>,
+    records: RefCell<Vec<RangeCheckRecord>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for RangeCheckChip<F> {
+    type Config = RangeCheckChipConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> RangeCheckChip<F> {
+    pub fn construct(config: RangeCheckChipConfig<F>) -> Self {
+        Self {
+            config,
+            records: RefCell::new(vec![]),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chip on the `value` column. The polynomial gate is built for
+    /// `polynomial_range` and split so that no constraint exceeds `max_degree`, while the
+    /// lookup and decomposition strategies share the tagged table of `decompose`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        polynomial_range: usize,
+        max_degree: usize,
+        decompose: DecomposeConfig<F>,
+    ) -> RangeCheckChipConfig<F> {
+        meta.enable_equality(value);
+
+        let shift = meta.fixed_column();
+        let shift_selector = meta.selector();
+
+        meta.create_gate("shift", |meta| {
+            let shift_selector = meta.query_selector(shift_selector);
+            let value_cur = meta.query_advice(value, Rotation::cur());
+            let value_next = meta.query_advice(value, Rotation::next());
+            let shift = meta.query_fixed(shift);
+
+            Constraints::with_selector(shift_selector, [("shifted value", value_cur + shift - value_next)])
+        });
+
+        let polynomial = ExpressionConfig::configure_with_max_degree(meta, value, polynomial_range, max_degree);
+
+        let lookup_selector = meta.complex_selector();
+        let lookup_table = decompose.lookup_table().value;
+
+        meta.lookup(|meta| {
+            let lookup_selector = meta.query_selector(lookup_selector);
+            let value = meta.query_advice(value, Rotation::cur());
+
+            vec![(lookup_selector * value, lookup_table)]
+        });
+
+        RangeCheckChipConfig {
+            value,
+            shift,
+            shift_selector,
+            polynomial,
+            lookup_selector,
+            decompose,
+            polynomial_range,
+        }
+    }
+
+    /// The range checks performed so far by this chip, in order.
+    pub fn records(&self) -> Vec<RangeCheckRecord> {
+        self.records.borrow().clone()
+    }
+
+    fn bit_length(value: u128) -> usize {
+        (u128::BITS - value.leading_zeros()) as usize
+    }
+
+    fn resolve(&self, range: u128, strategy: RangeCheckStrategy) -> RangeCheckStrategy {
+        let config = self.config();
+
+        let polynomial_range = config.polynomial_range as u128;
+        let lookup_range = config.lookup_table().lookup_range() as u128;
+
+        match strategy {
+            RangeCheckStrategy::Auto if range <= polynomial_range => RangeCheckStrategy::Polynomial,
+            RangeCheckStrategy::Auto if range <= lookup_range => RangeCheckStrategy::Lookup,
+            RangeCheckStrategy::Auto => RangeCheckStrategy::Decompose,
+            RangeCheckStrategy::Polynomial => {
+                assert!(range <= polynomial_range, "range exceeds the polynomial range");
+                strategy
+            }
+            RangeCheckStrategy::Lookup => {
+                assert!(range <= lookup_range, "range exceeds the lookup range");
+                strategy
+            }
+            RangeCheckStrategy::Decompose => strategy,
+        }
+    }
+
+    /// Constrains `value < range` with the given strategy, returning the strategy that was
+    /// actually used.
+    pub fn range_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        range: u128,
+        strategy: RangeCheckStrategy,
+    ) -> Result<RangeCheckStrategy, Error> {
+        assert!(range > 0);
+
+        let config = self.config();
+        let strategy = self.resolve(range, strategy);
+
+        match strategy {
+            RangeCheckStrategy::Polynomial | RangeCheckStrategy::Lookup => {
+                let bound = if strategy == RangeCheckStrategy::Polynomial {
+                    config.polynomial_range
+                } else {
+                    config.lookup_table().lookup_range()
+                } as u128;
+
+                layouter.assign_region(
+                    || "range check",
+                    |mut region| {
+                        let assign = |region: &mut _, value, offset| {
+                            if strategy == RangeCheckStrategy::Polynomial {
+                                config.polynomial.assign_in_region(region, value, offset)
+                            } else {
+                                config.lookup_selector.enable(region, offset)?;
+
+                                region.assign_advice(|| "value", config.value, offset, || value)
+                            }
+                        };
+
+                        let copied = assign(&mut region, value.value().copied(), 0)?;
+                        region.constrain_equal(value.cell(), copied.cell())?;
+
+                        if range < bound {
+                            let shift = F::from_u128(bound - range);
+
+                            config.shift_selector.enable(&mut region, 0)?;
+                            region.assign_fixed(|| "shift", config.shift, 0, || Value::known(shift))?;
+
+                            assign(&mut region, value.value().map(|v| *v + shift), 1)?;
+                        }
+
+                        Ok(())
+                    },
+                )?;
+            }
+            RangeCheckStrategy::Decompose => {
+                let num_bits = Self::bit_length(range - 1).max(1);
+
+                assert!(num_bits + 1 < F::NUM_BITS as usize);
+
+                let decompose_chip = DecomposeChip::construct(config.decompose.clone());
+
+                decompose_chip.assign(layouter.namespace(|| "decompose value"), value.clone(), num_bits, true)?;
+
+                // The shift 2^N - range is computed in the field, as 2^N overflows a u128
+                // for ranges above 2^127.
+                if !range.is_power_of_two() {
+                    let shift = F::from(2).pow_vartime([num_bits as u64]) - F::from_u128(range);
+
+                    let shifted = layouter.assign_region(
+                        || "shift value",
+                        |mut region| {
+                            config.shift_selector.enable(&mut region, 0)?;
+
+                            value.copy_advice(|| "value", &mut region, config.value, 0)?;
+                            region.assign_fixed(|| "shift", config.shift, 0, || Value::known(shift))?;
+
+                            region.assign_advice(|| "shifted value", config.value, 1, || value.value().map(|v| *v + shift))
+                        },
+                    )?;
+
+                    decompose_chip.assign(layouter.namespace(|| "decompose shifted value"), shifted, num_bits, true)?;
+                }
+            }
+            RangeCheckStrategy::Auto => unreachable!(),
+        }
+
+        self.records.borrow_mut().push(RangeCheckRecord { range, strategy });
+
+        Ok(strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::Circuit;
+
+    use super::*;
+
+    struct RangeCheckCircuit<F: PrimeField> {
+        value: Value<F>,
+        range: u128,
+        strategy: RangeCheckStrategy,
+        expected: RangeCheckStrategy,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for RangeCheckCircuit<F> {
+        type Config = RangeCheckChipConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                range: self.range,
+                strategy: self.strategy,
+                expected: self.expected,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            let running_sum = meta.advice_column();
+            let chunk = meta.advice_column();
+            let c_i_bits = meta.advice_column();
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << 4);
+            let decompose = DecomposeChip::configure(meta, running_sum, chunk, c_i_bits, lookup_table, 4);
+
+            RangeCheckChip::configure(meta, value, 8, 4, decompose)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let value = layouter.assign_region(|| "assign value", |mut region| {
+                region.assign_advice(|| "value", config.value, 0, || self.value)
+            })?;
+
+            let chip = RangeCheckChip::construct(config);
+
+            let strategy = chip.range_check(layouter.namespace(|| "range check"), value, self.range, self.strategy)?;
+
+            assert_eq!(strategy, self.expected);
+            assert_eq!(chip.records(), vec![RangeCheckRecord { range: self.range, strategy }]);
+
+            Ok(())
+        }
+    }
+
+    fn run(value: u64, range: u128, strategy: RangeCheckStrategy, expected: RangeCheckStrategy) -> bool {
+        let circuit = RangeCheckCircuit {
+            value: Value::known(Fp::from(value)),
+            range,
+            strategy,
+            expected,
+        };
+
+        MockProver::run(6, &circuit, vec![]).unwrap().verify().is_ok()
+    }
+
+    #[test]
+    fn test_auto_strategy() {
+        use RangeCheckStrategy::*;
+
+        for (range, expected) in [(5, Polynomial), (8, Polynomial), (10, Lookup), (16, Lookup), (1000, Decompose), (1 << 20, Decompose)] {
+            assert!(run(0, range, Auto, expected));
+            assert!(run(range as u64 - 1, range, Auto, expected));
+            assert!(!run(range as u64, range, Auto, expected));
+        }
+    }
+
+    #[test]
+    fn test_explicit_strategy() {
+        use RangeCheckStrategy::*;
+
+        for strategy in [Polynomial, Lookup, Decompose] {
+            for range in [3, 8] {
+                assert!(run(range as u64 - 1, range, strategy, strategy));
+                assert!(!run(range as u64, range, strategy, strategy));
+                assert!(!run(u64::MAX, range, strategy, strategy));
+            }
+        }
+    }
+
+    #[test]
+    fn test_polynomial_and_lookup() {
+        use RangeCheckStrategy::*;
+
+        for value in 0..8 {
+            assert!(run(value, 8, Polynomial, Polynomial));
+            assert!(run(value, 16, Lookup, Lookup));
+        }
+
+        assert!(run(15, 16, Lookup, Lookup));
+        assert!(!run(16, 16, Lookup, Lookup));
+    }
+
+    #[test]
+    fn test_range_above_2_127() {
+        use RangeCheckStrategy::*;
+
+        for range in [(1 << 127) + 1, u128::MAX] {
+            for value in [0, u64::MAX] {
+                let circuit = RangeCheckCircuit {
+                    value: Value::known(Fp::from(value)),
+                    range,
+                    strategy: Auto,
+                    expected: Decompose,
+                };
+
+                MockProver::run(8, &circuit, vec![]).unwrap().assert_satisfied();
+            }
+
+            let circuit = RangeCheckCircuit {
+                value: Value::known(Fp::from_u128(range)),
+                range,
+                strategy: Auto,
+                expected: Decompose,
+            };
+
+            assert!(MockProver::run(8, &circuit, vec![]).unwrap().verify().is_err());
+        }
+    }
+}
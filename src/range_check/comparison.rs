@@ -0,0 +1,286 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+
+/// This gadget compares two witnessed N-bit values `a` and `b`.
+///
+/// We witness the boolean `lt` and the difference `low` such that
+///
+/// ```text
+///  a - b + 2^N * lt = low
+/// ```
+///
+/// with `low` range-checked to N bits. For a, b in [0, 2^N) the left-hand side lies in
+/// [0, 2^N) for exactly one choice of `lt`, namely `lt = 1` iff `a < b`.
+///
+/// ```text
+///  |  a  |  b  |  low  |  lt  |  two_pow  | q_compare |
+///  ----------------------------------------------------
+///  |  a  |  b  |  low  |  lt  |    2^N    |     1     |
+/// ```
+///
+/// The gadget does not range-check `a` and `b` themselves: callers must already know
+/// that both fit in N bits, e.g. from a previous decomposition.
+#[derive(Clone, Debug)]
+pub struct ComparisonConfig<F: PrimeField> {
+    advice: [Column<Advice>; 4],
+    two_pow: Column<Fixed>,
+    selector: Selector,
+    decompose: DecomposeConfig<F>,
+}
+
+impl<F: PrimeField> ComparisonConfig<F> {
+    pub fn decompose(&self) -> &DecomposeConfig<F> {
+        &self.decompose
+    }
+}
+
+pub struct ComparisonChip<F: PrimeField> {
+    config: ComparisonConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for ComparisonChip<F> {
+    type Config = ComparisonConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> ComparisonChip<F> {
+    pub fn construct(config: ComparisonConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        decompose: DecomposeConfig<F>,
+    ) -> ComparisonConfig<F> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let two_pow = meta.fixed_column();
+        let selector = meta.selector();
+
+        meta.create_gate("compare", |meta| {
+            let selector = meta.query_selector(selector);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let low = meta.query_advice(advice[2], Rotation::cur());
+            let lt = meta.query_advice(advice[3], Rotation::cur());
+            let two_pow = meta.query_fixed(two_pow);
+
+            Constraints::with_selector(selector, [
+                ("shifted difference", a - b + two_pow * lt.clone() - low),
+                ("lt is boolean", lt.clone() * (Expression::Constant(F::ONE) - lt)),
+            ])
+        });
+
+        ComparisonConfig {
+            advice,
+            two_pow,
+            selector,
+            decompose,
+        }
+    }
+
+    /// Returns a boolean cell that is 1 iff `a < b`, for `a` and `b` in [0, 2^num_bits).
+    pub fn is_less_than(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        assert!(num_bits > 0 && num_bits + 1 < F::NUM_BITS as usize);
+
+        let two_pow = F::from(2).pow_vartime([num_bits as u64]);
+
+        let (low, lt) = layouter.assign_region(
+            || "less than",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                region.assign_fixed(|| "2^N", config.two_pow, 0, || Value::known(two_pow))?;
+
+                // a - b + 2^N has bit N set exactly when a >= b.
+                let difference = a.value().zip(b.value()).map(|(a, b)| *a - *b + two_pow);
+                let lt = difference.map(|d| {
+                    let geq = d.to_le_bits().iter().by_vals().skip(num_bits).any(|bit| bit);
+
+                    if geq { F::ZERO } else { F::ONE }
+                });
+                let low = difference.zip(lt).map(|(d, lt)| d - two_pow * (F::ONE - lt));
+
+                let low = region.assign_advice(|| "low", config.advice[2], 0, || low)?;
+                let lt = region.assign_advice(|| "lt", config.advice[3], 0, || lt)?;
+
+                Ok((low, lt))
+            },
+        )?;
+
+        let decompose_chip = DecomposeChip::construct(config.decompose.clone());
+
+        decompose_chip.assign(layouter.namespace(|| "range check low"), low, num_bits, true)?;
+
+        Ok(lt)
+    }
+
+    /// Returns a boolean cell that is 1 iff `a > b`, for `a` and `b` in [0, 2^num_bits).
+    pub fn is_greater_than(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.is_less_than(layouter, b, a, num_bits)
+    }
+
+    /// Constrains `a < b`, for `a` and `b` in [0, 2^num_bits).
+    pub fn assert_less_than(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        let lt = self.is_less_than(layouter.namespace(|| "is less than"), a, b, num_bits)?;
+
+        layouter.assign_region(
+            || "assert less than",
+            |mut region| region.constrain_constant(lt.cell(), F::ONE),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+    use super::*;
+
+    struct ComparisonCircuit<F: PrimeField> {
+        a: Value<F>,
+        b: Value<F>,
+        // Constrain `a < b` instead of exposing the comparison results.
+        assert: bool,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for ComparisonCircuit<F> {
+        type Config = (ComparisonConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: Value::unknown(),
+                b: Value::unknown(),
+                assert: self.assert,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let c_i_bits = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << 4);
+            let decompose = DecomposeChip::configure(meta, advice[2], advice[3], c_i_bits, lookup_table, 4);
+
+            (ComparisonChip::configure(meta, advice, decompose), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            config.decompose().lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let (a, b) = layouter.assign_region(|| "assign values", |mut region| {
+                let a = region.assign_advice(|| "a", config.advice[0], 0, || self.a)?;
+                let b = region.assign_advice(|| "b", config.advice[1], 0, || self.b)?;
+
+                Ok((a, b))
+            })?;
+
+            let chip = ComparisonChip::construct(config);
+
+            if self.assert {
+                return chip.assert_less_than(layouter.namespace(|| "assert less than"), a, b, 16);
+            }
+
+            let lt = chip.is_less_than(layouter.namespace(|| "less than"), a.clone(), b.clone(), 16)?;
+            let gt = chip.is_greater_than(layouter.namespace(|| "greater than"), a, b, 16)?;
+
+            layouter.constrain_instance(lt.cell(), instance, 0)?;
+            layouter.constrain_instance(gt.cell(), instance, 1)
+        }
+    }
+
+    fn circuit(a: u64, b: u64, assert: bool) -> ComparisonCircuit<Fp> {
+        ComparisonCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+            assert,
+        }
+    }
+
+    #[test]
+    fn test_is_less_than() {
+        for (a, b) in [(0, 0), (0, 1), (1, 0), (5, 1000), (1000, 5), (65535, 65535), (0, 65535), (65535, 0)] {
+            let public_inputs = vec![vec![Fp::from((a < b) as u64), Fp::from((a > b) as u64)]];
+
+            let prover = MockProver::run(6, &circuit(a, b, false), public_inputs).unwrap();
+
+            prover.assert_satisfied();
+
+            let public_inputs = vec![vec![Fp::from((a >= b) as u64), Fp::from((a > b) as u64)]];
+
+            let prover = MockProver::run(6, &circuit(a, b, false), public_inputs).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_assert_less_than() {
+        for (a, b) in [(0, 1), (5, 1000), (65534, 65535)] {
+            let prover = MockProver::run(6, &circuit(a, b, true), vec![vec![]]).unwrap();
+
+            prover.assert_satisfied();
+        }
+
+        for (a, b) in [(0, 0), (1000, 5), (65535, 0)] {
+            let prover = MockProver::run(6, &circuit(a, b, true), vec![vec![]]).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+    }
+}
@@ -1,14 +1,17 @@
+use std::marker::PhantomData;
+
 use ff::PrimeFieldBits;
 use halo2_proofs::{
-    circuit::{AssignedCell, Layouter},
-    plonk::{Assigned, ConstraintSystem, Error},
+    circuit::{AssignedCell, Chip, Layouter},
+    plonk::{ConstraintSystem, Error},
 };
 use halo2_proofs::circuit::Value;
 use halo2_proofs::pasta::group::ff::PrimeField;
-use halo2_proofs::plonk::{Advice, Column, Expression, Selector};
+use halo2_proofs::plonk::{Advice, Column, Constraints, Expression, Fixed, Selector};
 use halo2_proofs::poly::Rotation;
 
 use crate::gadgets::range_check_with_bits::RangeCheckConfig;
+use crate::range_check::lookup_with_bits::RangeCheckTable;
 
 /// This gadget range-constrains an element witnessed in the circuit to be N bits.
 ///
@@ -16,81 +19,167 @@ use crate::gadgets::range_check_with_bits::RangeCheckConfig;
 /// lookup table.
 ///
 /// Given an element `value`, we use a running sum to break it into K-bit chunks.
-/// Assume for now that N | K, and define C = N / K.
+/// Define C = ceil(N / K) and R = N mod K.
 ///
-///     value = [b_0, b_1, ..., b_{N-1}]   (little-endian)
-///           = c_0 + 2^K * c_1  + 2^{2K} * c_2 + ... + 2^{(C-1)K} * c_{C-1}
+/// ```text
+///  value = [b_0, b_1, ..., b_{N-1}]   (little-endian)
+///        = c_0 + 2^K * c_1  + 2^{2K} * c_2 + ... + 2^{(C-1)K} * c_{C-1}
+/// ```
 ///
 /// Initialise the running sum at
-///                                 value = z_0.
+/// ```text
+///                              value = z_0.
+/// ```
 ///
 /// Consequent terms of the running sum are z_{i+1} = (z_i - c_i) * 2^{-K}:
 ///
-///                           z_1 = (z_0 - c_0) * 2^{-K}
-///                           z_2 = (z_1 - c_1) * 2^{-K}
-///                              ...
-///                       z_{C-1} = c_{C-1}
-///                           z_C = (z_{C-1} - c_{C-1}) * 2^{-K}
-///                               = 0
+/// ```text
+///                        z_1 = (z_0 - c_0) * 2^{-K}
+///                        z_2 = (z_1 - c_1) * 2^{-K}
+///                           ...
+///                    z_{C-1} = c_{C-1}
+///                        z_C = (z_{C-1} - c_{C-1}) * 2^{-K}
+///                            = 0
+/// ```
 ///
-/// One configuration for this gadget could look like:
+/// Each chunk c_i is witnessed next to z_i, constrained to c_i = z_i - 2^K * z_{i+1}
+/// and looked up in the tagged table together with its minimal bit-length. When
+/// R != 0 the last chunk c_{C-1} only has R bits, so we additionally look up
+/// `R - bits(c_{C-1})` in the table, which proves `bits(c_{C-1}) <= R`.
 ///
-///     | running_sum |  q_decompose  |  table_value  |
-///     -----------------------------------------------
-///     |     z_0     |       1       |       0       |
-///     |     z_1     |       1       |       1       |
-///     |     ...     |      ...      |      ...      |
-///     |   z_{C-1}   |       1       |      ...      |
-///     |     z_C     |       0       |      ...      |
+/// ```text
+///  | running_sum |  chunk  |    c_i_bits   | q_decompose | q_partial | max_bits |
+///  -------------------------------------------------------------------------------
+///  |     z_0     |   c_0   |   bits(c_0)   |      1      |     0     |          |
+///  |     z_1     |   c_1   |   bits(c_1)   |      1      |     0     |          |
+///  |     ...     |   ...   |      ...      |     ...     |    ...    |          |
+///  |   z_{C-1}   | c_{C-1} | bits(c_{C-1}) |      1      |     1     |    R     |
+///  |     z_C     |         |               |      0      |     0     |          |
+/// ```
 ///
-/// Stretch task: use the tagged lookup table to constrain arbitrary bitlengths
-/// (even non-multiples of K)
+/// In non-strict mode z_C is not constrained to be zero, so the gadget instead proves
+/// `value = sum_i 2^{iK} c_i + 2^{CK} z_C`; this is only supported when K | N.
 #[derive(Debug, Clone)]
-struct DecomposeConfig<F: PrimeField> {
-    // You'll need an advice column to witness your running sum;
+pub struct DecomposeConfig<F: PrimeField> {
     running_sum: Column<Advice>,
+    chunk: Column<Advice>,
     c_i_bits: Column<Advice>,
-    // A selector to constrain the running sum;
-    // A selector to lookup the K-bit chunks;
+    max_bits: Column<Fixed>,
     decompose_selector: Selector,
-    // And of course, the K-bit lookup table
+    partial_selector: Selector,
     table: RangeCheckConfig<F>,
 
     lookup_bits: usize,
 }
 
-impl<F: PrimeField + PrimeFieldBits> DecomposeConfig<F> {
-    fn configure(meta: &mut ConstraintSystem<F>, running_sum: Column<Advice>, c_i_bits: Column<Advice>, lookup_bits: usize) -> Self {
-        // Create the needed columns and internal configs.
+impl<F: PrimeField> DecomposeConfig<F> {
+    pub fn lookup_bits(&self) -> usize {
+        self.lookup_bits
+    }
+
+    pub fn lookup_table(&self) -> &RangeCheckTable<F> {
+        &self.table.lookup_table
+    }
+}
+
+/// The cells assigned by [`DecomposeChip::assign`].
+#[derive(Clone, Debug)]
+pub struct RunningSum<F: PrimeField> {
+    /// The running sum z_0, ..., z_C, where z_0 is the decomposed value.
+    pub zs: Vec<AssignedCell<F, F>>,
+    /// The little-endian K-bit chunks c_0, ..., c_{C-1}.
+    pub chunks: Vec<AssignedCell<F, F>>,
+    /// The minimal bit-length of each chunk, as looked up in the tagged table
+    /// (a zero chunk is tagged with 1).
+    pub chunk_bits: Vec<AssignedCell<F, F>>,
+}
+
+pub struct DecomposeChip<F: PrimeField> {
+    config: DecomposeConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for DecomposeChip<F> {
+    type Config = DecomposeConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> DecomposeChip<F> {
+    pub fn construct(config: DecomposeConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        running_sum: Column<Advice>,
+        chunk: Column<Advice>,
+        c_i_bits: Column<Advice>,
+        lookup_table: RangeCheckTable<F>,
+        lookup_bits: usize,
+    ) -> DecomposeConfig<F> {
+        assert_eq!(lookup_table.lookup_range(), 1 << lookup_bits);
+
         let decompose_selector = meta.complex_selector();
+        let partial_selector = meta.complex_selector();
+
+        let max_bits = meta.fixed_column();
 
         let fixed_column = meta.fixed_column();
 
         meta.enable_constant(fixed_column);
 
         meta.enable_equality(running_sum);
+        meta.enable_equality(chunk);
+        meta.enable_equality(c_i_bits);
 
         let two_to_k = Expression::Constant(F::from(1 << lookup_bits));
 
-        // Range-constrain each K-bit chunk `c_i = z_i - z_{i+1} * 2^K` derived from the running sum.
+        meta.create_gate("running sum", |meta| {
+            let decompose_selector = meta.query_selector(decompose_selector);
+            let z_cur = meta.query_advice(running_sum, Rotation::cur());
+            let z_next = meta.query_advice(running_sum, Rotation::next());
+            let chunk = meta.query_advice(chunk, Rotation::cur());
+
+            Constraints::with_selector(decompose_selector, [("c_i = z_i - z_{i+1} * 2^K", z_cur - z_next * two_to_k - chunk)])
+        });
+
+        // Range-constrain each K-bit chunk `c_i` derived from the running sum.
         let table = RangeCheckConfig::configure(
             meta,
-            |meta| {
-                meta.query_advice(running_sum, Rotation::cur()) -
-                    meta.query_advice(running_sum, Rotation::next()) * two_to_k
-            },
-            |meta| {
-                meta.query_advice(c_i_bits, Rotation::next())
-            },
+            |meta| meta.query_advice(chunk, Rotation::cur()),
+            |meta| meta.query_advice(c_i_bits, Rotation::cur()),
             |meta| meta.query_selector(decompose_selector),
-            1 << lookup_bits,
+            lookup_table,
         );
 
+        // The last chunk must not have more than `max_bits` bits: `max_bits - bits(c_{C-1})` is
+        // looked up to show it is non-negative.
+        meta.lookup(|meta| {
+            let partial_selector = meta.query_selector(partial_selector);
+            let max_bits = meta.query_fixed(max_bits);
+            let bits = meta.query_advice(c_i_bits, Rotation::cur());
 
-        Self {
+            vec![(partial_selector * (max_bits - bits), table.lookup_table.value)]
+        });
+
+        DecomposeConfig {
             running_sum,
+            chunk,
             c_i_bits,
+            max_bits,
             decompose_selector,
+            partial_selector,
             table,
             lookup_bits,
         }
@@ -103,64 +192,108 @@ impl<F: PrimeField + PrimeFieldBits> DecomposeConfig<F> {
             .fold(0u64, |acc, (i, b)| acc + if *b { 1 << i } else { 0 })
     }
 
-    fn assign(
+    /// The integer value of the lowest 128 bits of `value`.
+    pub(crate) fn to_u128(value: &F) -> u128 {
+        value.to_le_bits().iter().by_vals().take(128).enumerate().fold(0, |acc, (i, bit)| acc | ((bit as u128) << i))
+    }
+
+    /// Splits the lowest `num_chunks * lookup_bits` bits of `value` into little-endian chunks
+    /// of `lookup_bits` bits, returning each chunk with its minimal bit-length.
+    pub(crate) fn chunks(value: F, num_chunks: usize, lookup_bits: usize) -> Vec<(u64, u64)> {
+        let bits: Vec<_> = value.to_le_bits().iter().by_vals().take(num_chunks * lookup_bits).collect();
+
+        bits.chunks(lookup_bits)
+            .map(|chunk| {
+                let chunk = Self::bits_to_u64_little_endian(chunk);
+                let chunk_bits = (64 - chunk.leading_zeros() as u64).max(1);
+
+                (chunk, chunk_bits)
+            })
+            .collect()
+    }
+
+    /// Decomposes `value` into K-bit chunks, range-constraining it to `num_bits` bits.
+    ///
+    /// If `strict` is false the final z_C is left unconstrained, and only the lowest
+    /// `num_bits` bits are decomposed; `num_bits` must then be a multiple of K.
+    pub fn assign(
         &self,
         mut layouter: impl Layouter<F>,
-        value: AssignedCell<Assigned<F>, F>,
+        value: AssignedCell<F, F>,
         num_bits: usize,
-    ) -> Result<(), Error> {
-        // 0. Copy in the witnessed `value`
+        strict: bool,
+    ) -> Result<RunningSum<F>, Error> {
+        assert!(num_bits > 0);
 
-        layouter.assign_region(|| "decompose", |mut region| {
-            let mut offset = 0;
+        let config = self.config();
+
+        let lookup_bits = config.lookup_bits;
+        let num_chunks = num_bits.div_ceil(lookup_bits);
+        let partial_bits = num_bits % lookup_bits;
+
+        assert!(strict || partial_bits == 0, "non-strict decomposition needs a multiple of K bits");
 
+        let two_to_k_inv = F::from(1 << lookup_bits).invert().unwrap();
+
+        layouter.assign_region(|| "decompose", |mut region| {
             let mut z = value.copy_advice(
                 || "copy first element of running sum",
                 &mut region,
-                self.running_sum,
-                offset,
+                config.running_sum,
+                0,
             )?;
 
-            let value: Value<Vec<_>> = value
-                .value()
-                .map(|v| v.evaluate().to_le_bits().iter().by_vals().take(num_bits).collect());
+            let chunks = value.value().map(|v| Self::chunks(*v, num_chunks, lookup_bits));
 
-            value.and_then(|v| {
-                for chunk in v.chunks(self.lookup_bits) {
-                    let mut zero_bits = 0;
+            let mut zs = vec![z.clone()];
+            let mut chunk_cells = vec![];
+            let mut chunk_bits = vec![];
 
-                    for i in (0..self.lookup_bits).rev() {
-                        let bit = chunk[i];
-                        if bit {
-                            break;
-                        }
-                        zero_bits += 1
-                    }
+            for i in 0..num_chunks {
+                config.decompose_selector.enable(&mut region, i)?;
 
-                    let mut chunk_bits = chunk.len() - zero_bits;
+                let chunk = chunks.as_ref().map(|chunks| chunks[i]);
 
-                    if chunk_bits == 0 {
-                        chunk_bits = 1;
-                    }
+                chunk_cells.push(region.assign_advice(
+                    || "c_i",
+                    config.chunk,
+                    i,
+                    || chunk.map(|(chunk, _)| F::from(chunk)),
+                )?);
+                chunk_bits.push(region.assign_advice(
+                    || "c_i_bits",
+                    config.c_i_bits,
+                    i,
+                    || chunk.map(|(_, bits)| F::from(bits)),
+                )?);
 
-                    let chunk_bits = Value::known(Assigned::from(F::from(chunk_bits as u64)));
+                let z_i = z.value().copied()
+                    .zip(chunk)
+                    .map(|(z, (chunk, _))| (z - F::from(chunk)) * two_to_k_inv);
 
-                    offset += 1;
-                    let chunk = Assigned::from(F::from(Self::bits_to_u64_little_endian(chunk)));
-
-                    let z_i = z.value().map(|v| (v - chunk) * Assigned::from(F::from(1u64 << self.lookup_bits)).invert());
+                z = region.assign_advice(|| "z_i", config.running_sum, i + 1, || z_i)?;
+                zs.push(z.clone());
+            }
 
-                    z = region.assign_advice(|| "z_i", self.running_sum, offset, || z_i).unwrap();
-                    region.assign_advice(|| "c_i_bits", self.c_i_bits, offset, || chunk_bits).unwrap();
-                }
-                Value::<F>::unknown()
-            });
+            if partial_bits != 0 {
+                config.partial_selector.enable(&mut region, num_chunks - 1)?;
+                region.assign_fixed(
+                    || "max bits of last chunk",
+                    config.max_bits,
+                    num_chunks - 1,
+                    || Value::known(F::from(partial_bits as u64)),
+                )?;
+            }
 
-            for i in 0..(num_bits / self.lookup_bits) {
-                self.decompose_selector.enable(&mut region, i)?;
+            if strict {
+                region.constrain_constant(z.cell(), F::ZERO)?;
             }
 
-            region.constrain_constant(z.cell(), F::ZERO)
+            Ok(RunningSum {
+                zs,
+                chunks: chunk_cells,
+                chunk_bits,
+            })
         })
     }
 }
@@ -170,52 +303,138 @@ mod tests {
     use halo2_proofs::circuit::SimpleFloorPlanner;
     use halo2_proofs::dev::MockProver;
     use halo2_proofs::pasta::Fp;
-    use halo2_proofs::plonk::Circuit;
+    use halo2_proofs::plonk::{Circuit, Instance};
 
     use super::*;
 
     #[derive(Default)]
     struct RangeCheckCircuit<F: PrimeField + PrimeFieldBits, const LOOKUP_BITS: usize> {
-        value: Value<Assigned<F>>,
+        value: Value<F>,
         num_bits: usize,
+        strict: bool,
     }
 
     impl<F: PrimeField + PrimeFieldBits, const LOOKUP_BITS: usize> Circuit<F> for RangeCheckCircuit<F, LOOKUP_BITS> {
-        type Config = DecomposeConfig<F>;
+        type Config = (DecomposeConfig<F>, Column<Advice>, Column<Instance>);
         type FloorPlanner = SimpleFloorPlanner;
 
         fn without_witnesses(&self) -> Self {
-            Self::default()
+            Self {
+                value: Value::unknown(),
+                num_bits: self.num_bits,
+                strict: self.strict,
+            }
         }
 
         fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
             let running_sum = meta.advice_column();
+            let chunk = meta.advice_column();
             let c_i_bits = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
 
-            DecomposeConfig::configure(meta, running_sum, c_i_bits, LOOKUP_BITS)
+            let lookup_table = RangeCheckTable::configure(meta, 1 << LOOKUP_BITS);
+
+            (DecomposeChip::configure(meta, running_sum, chunk, c_i_bits, lookup_table, LOOKUP_BITS), running_sum, instance)
         }
 
         fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
-            config.table.lookup_table.load(layouter.namespace(|| "lookup table"))?;
+            let (config, advice, instance) = config;
+
+            config.lookup_table().load(layouter.namespace(|| "lookup table"))?;
 
             let value = layouter.assign_region(|| "assign value somewhere", |mut region| {
-                region.assign_advice(|| "value", config.running_sum, 0, || self.value)
+                region.assign_advice(|| "value", advice, 0, || self.value)
             })?;
 
-            config.assign(layouter.namespace(|| "assign running sum"), value, self.num_bits)
+            let chip = DecomposeChip::construct(config);
+
+            let running_sum = chip.assign(layouter.namespace(|| "assign running sum"), value, self.num_bits, self.strict)?;
+
+            // Expose z_C followed by the chunks.
+            let z_c = running_sum.zs.last().unwrap();
+
+            for (row, cell) in std::iter::once(z_c).chain(running_sum.chunks.iter()).enumerate() {
+                layouter.constrain_instance(cell.cell(), instance, row)?;
+            }
+
+            Ok(())
         }
     }
 
+    fn public_inputs(value: u64, num_bits: usize, lookup_bits: usize) -> Vec<Vec<Fp>> {
+        let num_chunks = num_bits.div_ceil(lookup_bits);
+
+        let z_c = Fp::from(value >> (num_chunks * lookup_bits));
+        let chunks = (0..num_chunks).map(|i| Fp::from((value >> (i * lookup_bits)) % (1 << lookup_bits)));
+
+        vec![std::iter::once(z_c).chain(chunks).collect()]
+    }
+
 
     #[test]
     fn test_range_check() {
         let circuit = RangeCheckCircuit::<Fp, 4> {
-            value: Value::known(Fp::from(152).into()),
+            value: Value::known(Fp::from(152)),
             num_bits: 8,
+            strict: true,
+        };
+
+        let prover = MockProver::run(5, &circuit, public_inputs(152, 8, 4)).unwrap();
+
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_range_check_partial_chunk() {
+        for (value, num_bits) in [(2047, 11), (1023, 10), (0, 10), ((1 << 20) - 1, 20), ((1 << 52) - 1, 52)] {
+            let circuit = RangeCheckCircuit::<Fp, 4> {
+                value: Value::known(Fp::from(value)),
+                num_bits,
+                strict: true,
+            };
+
+            let prover = MockProver::run(6, &circuit, public_inputs(value, num_bits, 4)).unwrap();
+
+            prover.assert_satisfied();
+        }
+
+        for (value, num_bits) in [(2048, 11), (1024, 10), (1 << 52, 52)] {
+            let circuit = RangeCheckCircuit::<Fp, 4> {
+                value: Value::known(Fp::from(value)),
+                num_bits,
+                strict: true,
+            };
+
+            let prover = MockProver::run(6, &circuit, public_inputs(value, num_bits, 4)).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_non_strict_running_sum() {
+        let value = 0x1234_5678;
+
+        let circuit = RangeCheckCircuit::<Fp, 4> {
+            value: Value::known(Fp::from(value)),
+            num_bits: 16,
+            strict: false,
         };
 
-        let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+        let prover = MockProver::run(5, &circuit, public_inputs(value, 16, 4)).unwrap();
 
         prover.assert_satisfied();
+
+        let circuit = RangeCheckCircuit::<Fp, 4> {
+            value: Value::known(Fp::from(value)),
+            num_bits: 16,
+            strict: true,
+        };
+
+        let prover = MockProver::run(5, &circuit, public_inputs(value, 16, 4)).unwrap();
+
+        assert!(prover.verify().is_err());
     }
 }
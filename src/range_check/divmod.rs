@@ -0,0 +1,257 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::comparison::{ComparisonChip, ComparisonConfig};
+use crate::range_check::decompose::DecomposeChip;
+
+/// This gadget divides an N-bit integer `a` by an N-bit integer `b`, witnessing the
+/// quotient `q` and remainder `r` such that
+///
+/// ```text
+///  a = q * b + r,    0 <= r < b
+/// ```
+///
+/// ```text
+///  |  a  |  b  |  q  |  r  | q_divmod |
+///  ------------------------------------
+///  |  a  |  b  |  q  |  r  |    1     |
+/// ```
+///
+/// `q` and `r` are range-checked to N bits and `r < b` is enforced with the
+/// [`ComparisonChip`], which also rules out `b = 0`. Since 2N + 1 < F::NUM_BITS the
+/// product `q * b + r` cannot wrap, so the equation holds over the integers. As with the
+/// comparison, `a` and `b` must already be known to fit in N bits.
+#[derive(Clone, Debug)]
+pub struct DivModConfig<F: PrimeField> {
+    advice: [Column<Advice>; 4],
+    selector: Selector,
+    comparison: ComparisonConfig<F>,
+}
+
+impl<F: PrimeField> DivModConfig<F> {
+    pub fn comparison(&self) -> &ComparisonConfig<F> {
+        &self.comparison
+    }
+}
+
+/// The result of [`DivModChip::divmod`].
+#[derive(Clone, Debug)]
+pub struct QuotientAndRemainder<F: PrimeField> {
+    pub quotient: AssignedCell<F, F>,
+    pub remainder: AssignedCell<F, F>,
+}
+
+pub struct DivModChip<F: PrimeField> {
+    config: DivModConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for DivModChip<F> {
+    type Config = DivModConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> DivModChip<F> {
+    pub fn construct(config: DivModConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        comparison: ComparisonConfig<F>,
+    ) -> DivModConfig<F> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let selector = meta.selector();
+
+        meta.create_gate("divmod", |meta| {
+            let selector = meta.query_selector(selector);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let q = meta.query_advice(advice[2], Rotation::cur());
+            let r = meta.query_advice(advice[3], Rotation::cur());
+
+            Constraints::with_selector(selector, [("a = q * b + r", a - q * b - r)])
+        });
+
+        DivModConfig {
+            advice,
+            selector,
+            comparison,
+        }
+    }
+
+    /// Returns the quotient and remainder of `a / b`, for `a` and `b` in [0, 2^num_bits)
+    /// and `b` nonzero.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Synthesis` if the low 128 bits of `b` are known to be zero, which
+    /// includes `b = 0`, before any witness is assigned for the quotient. Without witnesses the `r < b` comparison rejects a zero
+    /// divisor in the proof.
+    pub fn divmod(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<QuotientAndRemainder<F>, Error> {
+        assert!(num_bits > 0 && num_bits <= 128 && 2 * num_bits + 1 < F::NUM_BITS as usize);
+
+        let config = self.config();
+
+        let (quotient, remainder) = layouter.assign_region(
+            || "divmod",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                // Surface a zero divisor now rather than as an unsatisfied comparison. The
+                // check runs on the truncated divisor that the division below uses.
+                b.value().map(DecomposeChip::to_u128).error_if_known_and(|b| *b == 0)?;
+
+                let result = a.value().zip(b.value()).map(|(a, b)| {
+                    let (a, b) = (DecomposeChip::to_u128(a), DecomposeChip::to_u128(b));
+
+                    (F::from_u128(a / b), F::from_u128(a % b))
+                });
+
+                let quotient = region.assign_advice(|| "q", config.advice[2], 0, || result.map(|(q, _)| q))?;
+                let remainder = region.assign_advice(|| "r", config.advice[3], 0, || result.map(|(_, r)| r))?;
+
+                Ok((quotient, remainder))
+            },
+        )?;
+
+        let decompose_chip = DecomposeChip::construct(config.comparison.decompose().clone());
+
+        decompose_chip.assign(layouter.namespace(|| "range check q"), quotient.clone(), num_bits, true)?;
+        decompose_chip.assign(layouter.namespace(|| "range check r"), remainder.clone(), num_bits, true)?;
+
+        let comparison_chip = ComparisonChip::construct(config.comparison.clone());
+
+        comparison_chip.assert_less_than(layouter.namespace(|| "r < b"), remainder.clone(), b, num_bits)?;
+
+        Ok(QuotientAndRemainder { quotient, remainder })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Value};
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+    use super::*;
+
+    const NUM_BITS: usize = 16;
+
+    struct DivModCircuit<F: PrimeField> {
+        a: Value<F>,
+        b: Value<F>,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for DivModCircuit<F> {
+        type Config = (DivModConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: Value::unknown(),
+                b: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let c_i_bits = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << 4);
+            let decompose = DecomposeChip::configure(meta, advice[2], advice[3], c_i_bits, lookup_table, 4);
+            let comparison = ComparisonChip::configure(meta, advice, decompose);
+
+            (DivModChip::configure(meta, advice, comparison), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            config.comparison().decompose().lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let (a, b) = layouter.assign_region(|| "assign values", |mut region| {
+                let a = region.assign_advice(|| "a", config.advice[0], 0, || self.a)?;
+                let b = region.assign_advice(|| "b", config.advice[1], 0, || self.b)?;
+
+                Ok((a, b))
+            })?;
+
+            let chip = DivModChip::construct(config);
+
+            let result = chip.divmod(layouter.namespace(|| "divmod"), a, b, NUM_BITS)?;
+
+            layouter.constrain_instance(result.quotient.cell(), instance, 0)?;
+            layouter.constrain_instance(result.remainder.cell(), instance, 1)
+        }
+    }
+
+    fn circuit(a: u64, b: u64) -> DivModCircuit<Fp> {
+        DivModCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        }
+    }
+
+    #[test]
+    fn test_divmod() {
+        for (a, b) in [(17, 5), (0, 7), (5, 17), (65535, 1), (65535, 65535), (65535, 256), (1000, 10)] {
+            let prover = MockProver::run(7, &circuit(a, b), vec![vec![Fp::from(a / b), Fp::from(a % b)]]).unwrap();
+
+            prover.assert_satisfied();
+
+            // A wrong quotient is rejected.
+            let prover = MockProver::run(7, &circuit(a, b), vec![vec![Fp::from(a / b + 1), Fp::from(a % b)]]).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_divmod_by_zero() {
+        assert!(matches!(MockProver::run(7, &circuit(17, 0), vec![vec![Fp::zero(), Fp::zero()]]), Err(Error::Synthesis)));
+
+        // A divisor of 2^128 does not fit in N bits and truncates to zero.
+        let circuit = DivModCircuit {
+            a: Value::known(Fp::from(17)),
+            b: Value::known(Fp::from_u128(1 << 127).double()),
+        };
+
+        assert!(matches!(MockProver::run(7, &circuit, vec![vec![Fp::zero(), Fp::zero()]]), Err(Error::Synthesis)));
+    }
+}
@@ -1,60 +1,127 @@
 use std::marker::PhantomData;
+use std::ops::Range;
 
-use halo2_proofs::circuit::{Layouter, Value};
+use halo2_proofs::circuit::{AssignedCell, Region, Value};
 use halo2_proofs::pasta::group::ff::PrimeField;
-use halo2_proofs::plonk::{Advice, Assigned, Column, Constraints, ConstraintSystem, Error, Expression, Selector};
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Selector};
 use halo2_proofs::poly::Rotation;
 
+/// Range-constrains `value` to [0, range) with the product gate
+///
+/// ```text
+///  (0 - value) * (1 - value) * ... * (range - 1 - value) = 0.
+/// ```
+///
+/// To respect a maximum degree, the factors are split into groups
+/// whose partial products are witnessed in extra advice columns on the same row:
+///
+/// ```text
+///  partial_0 = g_0(value)
+///  partial_j = partial_{j-1} * g_j(value)
+///          0 = partial_{m-1} * g_m(value)
+/// ```
+///
+/// where each g_j is a product of consecutive factors. The selector counts towards the
+/// degree, so the first group has `max_degree - 1` factors and later ones `max_degree - 2`.
 #[derive(Clone, Debug)]
-struct ExpressionConfig<F: PrimeField> {
+pub(crate) struct ExpressionConfig<F: PrimeField> {
     value: Column<Advice>,
+    partials: Vec<Column<Advice>>,
+    groups: Vec<Range<u64>>,
     selector: Selector,
     _marker: PhantomData<F>,
 }
 
 impl<F: PrimeField> ExpressionConfig<F> {
-    fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>, range: usize) -> Self {
+    /// Configures the gate for [0, range), split so that no constraint exceeds
+    /// `max_degree`. A `max_degree` of `range + 1` keeps the single product gate.
+    pub(crate) fn configure_with_max_degree(meta: &mut ConstraintSystem<F>, value: Column<Advice>, range: usize, max_degree: usize) -> Self {
+        assert!(max_degree >= 3);
+
         let selector = meta.selector();
 
+        let range = range as u64;
+
+        let mut groups = vec![];
+        let mut start = 0;
+
+        while start < range || groups.is_empty() {
+            let group_size = if groups.is_empty() { max_degree - 1 } else { max_degree - 2 };
+            let end = (start + group_size as u64).min(range);
+
+            groups.push(start..end);
+            start = end;
+        }
+
+        let partials: Vec<_> = (1..groups.len()).map(|_| meta.advice_column()).collect();
+
         meta.create_gate(
             "Range check",
             |meta| {
                 let value = meta.query_advice(value, Rotation::cur());
                 let selector = meta.query_selector(selector);
 
-                let value = (0..range).fold(value.clone(), |expr, i| {
-                    expr * (Expression::Constant(F::from(i as u64)) - value.clone())
-                });
+                let products: Vec<_> = groups
+                    .iter()
+                    .map(|group| {
+                        group.clone().fold(Expression::Constant(F::ONE), |expr, i| {
+                            expr * (Expression::Constant(F::from(i)) - value.clone())
+                        })
+                    })
+                    .collect();
 
-                Constraints::with_selector(selector, [("range check", value)])
+                let partials: Vec<_> = partials
+                    .iter()
+                    .map(|&partial| meta.query_advice(partial, Rotation::cur()))
+                    .collect();
+
+                let mut constraints = vec![];
+
+                for (j, product) in products.into_iter().enumerate() {
+                    let product = if j == 0 { product } else { partials[j - 1].clone() * product };
+
+                    match partials.get(j) {
+                        Some(partial) => constraints.push(("partial product", product - partial.clone())),
+                        None => constraints.push(("range check", product)),
+                    }
+                }
+
+                Constraints::with_selector(selector, constraints)
             },
         );
 
         Self {
             value,
+            partials,
+            groups,
             selector,
             _marker: PhantomData,
         }
     }
 
-    fn assign(&self, mut layouter: impl Layouter<F>, value: Value<Assigned<F>>) -> Result<(), Error> {
-        layouter.assign_region(
-            || "range check",
-            |mut region| {
-                self.selector.enable(&mut region, 0)?;
-                region.assign_advice(|| "value", self.value, 0, || value)?;
+    pub(crate) fn assign_in_region(&self, region: &mut Region<'_, F>, value: Value<F>, offset: usize) -> Result<AssignedCell<F, F>, Error> {
+        self.selector.enable(region, offset)?;
+
+        let mut partial = Value::known(F::ONE);
+
+        for (group, &column) in self.groups.iter().zip(self.partials.iter()) {
+            partial = partial.zip(value).map(|(partial, value)| {
+                group.clone().fold(partial, |acc, i| acc * (F::from(i) - value))
+            });
+
+            region.assign_advice(|| "partial product", column, offset, || partial)?;
+        }
 
-                Ok(())
-            })
+        region.assign_advice(|| "value", self.value, offset, || value)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner};
     use halo2_proofs::dev::{FailureLocation, MockProver, VerifyFailure};
     use halo2_proofs::pasta::Fp;
-    use halo2_proofs::plonk::{Any, Circuit};
+    use halo2_proofs::plonk::{Any, Assigned, Circuit};
 
     use super::*;
 
@@ -74,11 +141,41 @@ mod tests {
         fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
             let advice = meta.advice_column();
 
-            ExpressionConfig::configure(meta, advice, RANGE)
+            ExpressionConfig::configure_with_max_degree(meta, advice, RANGE, RANGE + 1)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            layouter.assign_region(
+                || "range check",
+                |mut region| config.assign_in_region(&mut region, self.value.evaluate(), 0).map(|_| ()),
+            )
+        }
+    }
+
+    #[derive(Default)]
+    struct SplitCircuit<F: PrimeField, const RANGE: usize, const MAX_DEGREE: usize> {
+        value: Value<F>,
+    }
+
+    impl<F: PrimeField, const RANGE: usize, const MAX_DEGREE: usize> Circuit<F> for SplitCircuit<F, RANGE, MAX_DEGREE> {
+        type Config = ExpressionConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+
+            ExpressionConfig::configure_with_max_degree(meta, advice, RANGE, MAX_DEGREE)
         }
 
         fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
-            config.assign(layouter.namespace(|| "Range check"), self.value)
+            layouter.assign_region(
+                || "range check",
+                |mut region| config.assign_in_region(&mut region, self.value, 0).map(|_| ()),
+            )
         }
     }
 
@@ -117,4 +214,37 @@ mod tests {
             }])
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_split_range_check() {
+        let k = 4;
+
+        const RANGE: usize = 40;
+        const MAX_DEGREE: usize = 5;
+
+        let mut meta = ConstraintSystem::<Fp>::default();
+        SplitCircuit::<Fp, RANGE, MAX_DEGREE>::configure(&mut meta);
+
+        assert!(meta.degree() <= MAX_DEGREE);
+
+        for i in 0..RANGE {
+            let circuit = SplitCircuit::<Fp, RANGE, MAX_DEGREE> {
+                value: Value::known(Fp::from(i as u64))
+            };
+
+            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+
+            prover.assert_satisfied();
+        }
+
+        for value in [RANGE as u64, 1 << 20] {
+            let circuit = SplitCircuit::<Fp, RANGE, MAX_DEGREE> {
+                value: Value::known(Fp::from(value))
+            };
+
+            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+    }
+}
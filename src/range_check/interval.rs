@@ -0,0 +1,345 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+
+/// This gadget checks that a witnessed element lies in the interval [lo, hi) for
+/// arbitrary constant bounds.
+///
+/// Each bound is compared against the value with an M-bit decomposition: for a bound
+/// `b` we witness
+///
+/// ```text
+///  value - b + 2^M = low + 2^M * top
+/// ```
+///
+/// with `low` range-checked to M bits and `top` boolean. As long as the value lies in
+/// [b - 2^M, b + 2^M), this is the unique decomposition and `top = 1` iff `value >= b`.
+/// The output is then `in_range = top_lo * (1 - top_hi)`.
+///
+/// ```text
+///  | advice_0 | advice_1 | advice_2 |  bound  | two_pow | q_compare | q_interval |
+///  ---------------------------------------------------------------------------------
+///  |  value   |  low_lo  |  top_lo  |   lo    |   2^M   |     1     |     1      |
+///  |  value   |  low_hi  |  top_hi  |   hi    |   2^M   |     1     |     0      |
+///  | in_range |          |          |         |         |     0     |     0      |
+/// ```
+///
+/// To only constrain membership, we choose M with 2^M >= hi - lo and constrain
+/// `in_range = 1`: then `value - lo` and `value - hi + 2^M` are both M-bit values,
+/// which holds exactly for values in [lo, hi), without any bound on the value itself.
+#[derive(Clone, Debug)]
+pub struct IntervalConfig<F: PrimeField> {
+    advice: [Column<Advice>; 3],
+    bound: Column<Fixed>,
+    two_pow: Column<Fixed>,
+    compare_selector: Selector,
+    interval_selector: Selector,
+    decompose: DecomposeConfig<F>,
+}
+
+pub struct IntervalChip<F: PrimeField> {
+    config: IntervalConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for IntervalChip<F> {
+    type Config = IntervalConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> IntervalChip<F> {
+    pub fn construct(config: IntervalConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        decompose: DecomposeConfig<F>,
+    ) -> IntervalConfig<F> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let bound = meta.fixed_column();
+        let two_pow = meta.fixed_column();
+
+        let compare_selector = meta.selector();
+        let interval_selector = meta.selector();
+
+        meta.create_gate("compare with bound", |meta| {
+            let compare_selector = meta.query_selector(compare_selector);
+            let value = meta.query_advice(advice[0], Rotation::cur());
+            let low = meta.query_advice(advice[1], Rotation::cur());
+            let top = meta.query_advice(advice[2], Rotation::cur());
+            let bound = meta.query_fixed(bound);
+            let two_pow = meta.query_fixed(two_pow);
+
+            Constraints::with_selector(compare_selector, [
+                ("decompose difference", value - bound + two_pow.clone() - low - two_pow * top.clone()),
+                ("top is boolean", top.clone() * (Expression::Constant(F::ONE) - top)),
+            ])
+        });
+
+        meta.create_gate("interval", |meta| {
+            let interval_selector = meta.query_selector(interval_selector);
+            let top_lo = meta.query_advice(advice[2], Rotation::cur());
+            let top_hi = meta.query_advice(advice[2], Rotation::next());
+            let in_range = meta.query_advice(advice[0], Rotation(2));
+
+            Constraints::with_selector(interval_selector, [
+                ("in range", top_lo * (Expression::Constant(F::ONE) - top_hi) - in_range),
+            ])
+        });
+
+        IntervalConfig {
+            advice,
+            bound,
+            two_pow,
+            compare_selector,
+            interval_selector,
+            decompose,
+        }
+    }
+
+    fn bit_length(value: F) -> usize {
+        value.to_le_bits().iter().by_vals().rposition(|bit| bit).map_or(0, |i| i + 1)
+    }
+
+    /// Returns a boolean cell that is 1 iff `lo <= value < hi`.
+    ///
+    /// `num_bits` must satisfy `2^num_bits >= hi - lo`, and the result is only defined
+    /// for values in [hi - 2^num_bits, lo + 2^num_bits); in particular it is correct for
+    /// any value and bounds below `2^num_bits`. No witness exists for other values.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Synthesis` if the interval is empty (`hi <= lo`) or wider than
+    /// `2^num_bits`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits + 1` is not below `F::NUM_BITS`.
+    pub fn is_in_range(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        lo: F,
+        hi: F,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        assert!(num_bits + 1 < F::NUM_BITS as usize);
+
+        // With num_bits + 1 < F::NUM_BITS, an empty interval wraps hi - lo - 1 around the
+        // modulus and fails this check as well.
+        if Self::bit_length(hi - lo - F::ONE) > num_bits {
+            return Err(Error::Synthesis);
+        }
+
+        let two_pow = F::from(2).pow_vartime([num_bits as u64]);
+
+        let low_cells = layouter.assign_region(
+            || "interval",
+            |mut region| {
+                config.compare_selector.enable(&mut region, 0)?;
+                config.compare_selector.enable(&mut region, 1)?;
+                config.interval_selector.enable(&mut region, 0)?;
+
+                let mut low_cells = vec![];
+                let mut tops = vec![];
+
+                for (offset, bound) in [lo, hi].into_iter().enumerate() {
+                    value.copy_advice(|| "value", &mut region, config.advice[0], offset)?;
+
+                    region.assign_fixed(|| "bound", config.bound, offset, || Value::known(bound))?;
+                    region.assign_fixed(|| "2^M", config.two_pow, offset, || Value::known(two_pow))?;
+
+                    let difference = value.value().map(|v| *v - bound + two_pow);
+                    let top = difference.map(|d| if Self::bit_length(d) > num_bits { F::ONE } else { F::ZERO });
+                    let low = difference.zip(top).map(|(d, top)| d - top * two_pow);
+
+                    low_cells.push(region.assign_advice(|| "low", config.advice[1], offset, || low)?);
+                    tops.push(region.assign_advice(|| "top", config.advice[2], offset, || top)?);
+                }
+
+                let in_range = tops[0].value().copied() * tops[1].value().map(|top| F::ONE - top);
+
+                let in_range = region.assign_advice(|| "in range", config.advice[0], 2, || in_range)?;
+
+                low_cells.push(in_range);
+
+                Ok(low_cells)
+            },
+        )?;
+
+        let [low_lo, low_hi, in_range]: [AssignedCell<F, F>; 3] = low_cells.try_into().unwrap();
+
+        let decompose_chip = DecomposeChip::construct(config.decompose.clone());
+
+        decompose_chip.assign(layouter.namespace(|| "range check low_lo"), low_lo, num_bits, true)?;
+        decompose_chip.assign(layouter.namespace(|| "range check low_hi"), low_hi, num_bits, true)?;
+
+        Ok(in_range)
+    }
+
+    /// Constrains `lo <= value < hi`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Synthesis` if the interval is empty (`hi <= lo`) or too wide to be
+    /// checked in this field.
+    pub fn constrain_in_range(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        lo: F,
+        hi: F,
+    ) -> Result<(), Error> {
+        let num_bits = Self::bit_length(hi - lo - F::ONE).max(1);
+
+        if num_bits + 1 >= F::NUM_BITS as usize {
+            return Err(Error::Synthesis);
+        }
+
+        let in_range = self.is_in_range(layouter.namespace(|| "is in range"), value, lo, hi, num_bits)?;
+
+        layouter.assign_region(
+            || "constrain in range",
+            |mut region| region.constrain_constant(in_range.cell(), F::ONE),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::Circuit;
+
+    use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+    use super::*;
+
+    struct IntervalCircuit<F: PrimeField> {
+        value: Value<F>,
+        lo: F,
+        hi: F,
+        // `None` to constrain membership, otherwise the expected boolean output.
+        expected: Option<bool>,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for IntervalCircuit<F> {
+        type Config = IntervalConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                lo: self.lo,
+                hi: self.hi,
+                expected: self.expected,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column(), meta.advice_column()];
+            let c_i_bits = meta.advice_column();
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << 4);
+            let decompose = DecomposeChip::configure(meta, advice[1], advice[2], c_i_bits, lookup_table, 4);
+
+            IntervalChip::configure(meta, advice, decompose)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.decompose.lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let value = layouter.assign_region(|| "assign value", |mut region| {
+                region.assign_advice(|| "value", config.advice[0], 0, || self.value)
+            })?;
+
+            let chip = IntervalChip::construct(config);
+
+            match self.expected {
+                None => chip.constrain_in_range(layouter.namespace(|| "interval"), value, self.lo, self.hi),
+                Some(expected) => {
+                    let in_range = chip.is_in_range(layouter.namespace(|| "interval"), value, self.lo, self.hi, 32)?;
+
+                    layouter.assign_region(
+                        || "check output",
+                        |mut region| region.constrain_constant(in_range.cell(), F::from(expected as u64)),
+                    )
+                }
+            }
+        }
+    }
+
+    fn run(value: u64, lo: u64, hi: u64, expected: Option<bool>) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+        let circuit = IntervalCircuit {
+            value: Value::known(Fp::from(value)),
+            lo: Fp::from(lo),
+            hi: Fp::from(hi),
+            expected,
+        };
+
+        MockProver::run(7, &circuit, vec![]).unwrap().verify()
+    }
+
+    #[test]
+    fn test_constrain_in_range() {
+        for value in [1900, 1999, 2099] {
+            assert_eq!(run(value, 1900, 2100, None), Ok(()));
+        }
+
+        for value in [0, 1899, 2100, 1 << 40] {
+            assert!(run(value, 1900, 2100, None).is_err());
+        }
+
+        assert_eq!(run(1, 1, 1_000_000_001, None), Ok(()));
+        assert_eq!(run(1_000_000_000, 1, 1_000_000_001, None), Ok(()));
+        assert!(run(0, 1, 1_000_000_001, None).is_err());
+        assert!(run(1_000_000_001, 1, 1_000_000_001, None).is_err());
+    }
+
+    #[test]
+    fn test_is_in_range() {
+        for (value, expected) in [(0, false), (1899, false), (1900, true), (2099, true), (2100, false), (u32::MAX as u64, false)] {
+            assert_eq!(run(value, 1900, 2100, Some(expected)), Ok(()));
+            assert!(run(value, 1900, 2100, Some(!expected)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_bounds() {
+        for (lo, hi, expected) in [(2100, 1900, None), (1900, 1900, None), (2100, 1900, Some(false)), (0, 1 << 33, Some(true))] {
+            let circuit = IntervalCircuit {
+                value: Value::known(Fp::from(2000)),
+                lo: Fp::from(lo),
+                hi: Fp::from(hi),
+                expected,
+            };
+
+            assert!(matches!(MockProver::run(7, &circuit, vec![]), Err(Error::Synthesis)));
+        }
+    }
+}
@@ -21,6 +21,10 @@ impl<F: PrimeField> RangeCheckTable<F> {
         }
     }
 
+    pub fn lookup_range(&self) -> usize {
+        self.lookup_range
+    }
+
     pub fn load(&self, mut layouter: impl Layouter<F>)-> Result<(), Error> {
         layouter.assign_table(|| "Assign lookup table", |mut table| {
             for i in 0..self.lookup_range {
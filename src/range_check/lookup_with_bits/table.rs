@@ -24,6 +24,10 @@ impl<F: PrimeField> RangeCheckTable<F> {
         }
     }
 
+    pub fn lookup_range(&self) -> usize {
+        self.lookup_range
+    }
+
     pub fn load(&self, mut layouter: impl Layouter<F>)-> Result<(), Error> {
         layouter.assign_table(|| "Assign lookup table", |mut table| {
             let mut bits = 1;
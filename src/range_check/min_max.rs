@@ -0,0 +1,296 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::comparison::{ComparisonChip, ComparisonConfig};
+
+/// This gadget computes minima and maxima of N-bit values.
+///
+/// Each step compares `a` and `b` with the [`ComparisonChip`] and then selects one of
+/// them with the boolean `lt = [a < b]`:
+///
+/// ```text
+///  out = lt * (x - y) + y
+/// ```
+///
+/// where `(x, y) = (a, b)` for the minimum and `(x, y) = (b, a)` for the maximum.
+///
+/// ```text
+///  |  x  |  y  |  lt  |  out  | q_select |
+///  ---------------------------------------
+///  |  x  |  y  |  lt  |  out  |    1     |
+/// ```
+///
+/// As with the comparison, all inputs must already be known to fit in N bits.
+#[derive(Clone, Debug)]
+pub struct MinMaxConfig<F: PrimeField> {
+    advice: [Column<Advice>; 4],
+    selector: Selector,
+    comparison: ComparisonConfig<F>,
+}
+
+impl<F: PrimeField> MinMaxConfig<F> {
+    pub fn comparison(&self) -> &ComparisonConfig<F> {
+        &self.comparison
+    }
+}
+
+pub struct MinMaxChip<F: PrimeField> {
+    config: MinMaxConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for MinMaxChip<F> {
+    type Config = MinMaxConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> MinMaxChip<F> {
+    pub fn construct(config: MinMaxConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        comparison: ComparisonConfig<F>,
+    ) -> MinMaxConfig<F> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let selector = meta.selector();
+
+        meta.create_gate("select", |meta| {
+            let selector = meta.query_selector(selector);
+            let x = meta.query_advice(advice[0], Rotation::cur());
+            let y = meta.query_advice(advice[1], Rotation::cur());
+            let lt = meta.query_advice(advice[2], Rotation::cur());
+            let out = meta.query_advice(advice[3], Rotation::cur());
+
+            Constraints::with_selector(selector, [("select", lt * (x - y.clone()) + y - out)])
+        });
+
+        MinMaxConfig {
+            advice,
+            selector,
+            comparison,
+        }
+    }
+
+    // Returns `x` if `a < b` and `y` otherwise, where `(x, y)` is `(a, b)` or `(b, a)`.
+    fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+        min: bool,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let comparison_chip = ComparisonChip::construct(config.comparison.clone());
+        let lt = comparison_chip.is_less_than(layouter.namespace(|| "compare"), a.clone(), b.clone(), num_bits)?;
+
+        let (x, y) = if min { (a, b) } else { (b, a) };
+
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                x.copy_advice(|| "x", &mut region, config.advice[0], 0)?;
+                y.copy_advice(|| "y", &mut region, config.advice[1], 0)?;
+                lt.copy_advice(|| "lt", &mut region, config.advice[2], 0)?;
+
+                let out = lt.value().zip(x.value()).zip(y.value()).map(|((lt, x), y)| *lt * (*x - *y) + *y);
+
+                region.assign_advice(|| "out", config.advice[3], 0, || out)
+            },
+        )
+    }
+
+    /// Returns the smaller of `a` and `b`, for `a` and `b` in [0, 2^num_bits).
+    pub fn min(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.select(layouter, a, b, num_bits, true)
+    }
+
+    /// Returns the larger of `a` and `b`, for `a` and `b` in [0, 2^num_bits).
+    pub fn max(
+        &self,
+        layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.select(layouter, a, b, num_bits, false)
+    }
+
+    /// Returns the largest of `values`, all in [0, 2^num_bits).
+    pub fn max_of(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>],
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let (first, rest) = values.split_first().expect("max_of needs at least one value");
+
+        rest.iter().enumerate().try_fold(first.clone(), |max, (i, value)| {
+            self.max(layouter.namespace(|| format!("max {}", i)), max, value.clone(), num_bits)
+        })
+    }
+
+    /// Returns `x` clamped to [lo, hi], that is `max(lo, min(x, hi))`, for `x`, `lo` and
+    /// `hi` in [0, 2^num_bits). For `lo > hi` the result is `lo`.
+    pub fn clamp(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: AssignedCell<F, F>,
+        lo: AssignedCell<F, F>,
+        hi: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let upper = self.min(layouter.namespace(|| "clamp to hi"), x, hi, num_bits)?;
+
+        self.max(layouter.namespace(|| "clamp to lo"), lo, upper, num_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::{SimpleFloorPlanner, Value};
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use crate::range_check::decompose::DecomposeChip;
+    use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+    use super::*;
+
+    const NUM_BITS: usize = 16;
+
+    struct MinMaxCircuit<F: PrimeField> {
+        values: Vec<Value<F>>,
+        lo: Value<F>,
+        hi: Value<F>,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for MinMaxCircuit<F> {
+        type Config = (MinMaxConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![Value::unknown(); self.values.len()],
+                lo: Value::unknown(),
+                hi: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let c_i_bits = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << 4);
+            let decompose = DecomposeChip::configure(meta, advice[2], advice[3], c_i_bits, lookup_table, 4);
+            let comparison = ComparisonChip::configure(meta, advice, decompose);
+
+            (MinMaxChip::configure(meta, advice, comparison), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            config.comparison().decompose().lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let (values, lo, hi) = layouter.assign_region(|| "assign values", |mut region| {
+                let values = self.values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| region.assign_advice(|| "value", config.advice[0], i, || *value))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let lo = region.assign_advice(|| "lo", config.advice[1], 0, || self.lo)?;
+                let hi = region.assign_advice(|| "hi", config.advice[1], 1, || self.hi)?;
+
+                Ok((values, lo, hi))
+            })?;
+
+            let chip = MinMaxChip::construct(config);
+
+            let min = chip.min(layouter.namespace(|| "min"), values[0].clone(), values[1].clone(), NUM_BITS)?;
+            let max = chip.max(layouter.namespace(|| "max"), values[0].clone(), values[1].clone(), NUM_BITS)?;
+            let max_of = chip.max_of(layouter.namespace(|| "max of"), &values, NUM_BITS)?;
+            let clamped = chip.clamp(layouter.namespace(|| "clamp"), values[0].clone(), lo, hi, NUM_BITS)?;
+
+            for (i, cell) in [min, max, max_of, clamped].iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn run(values: &[u64], lo: u64, hi: u64, expected: [u64; 4]) -> bool {
+        let circuit = MinMaxCircuit {
+            values: values.iter().map(|&value| Value::known(Fp::from(value))).collect(),
+            lo: Value::known(Fp::from(lo)),
+            hi: Value::known(Fp::from(hi)),
+        };
+
+        let public_inputs = vec![expected.iter().map(|&value| Fp::from(value)).collect()];
+
+        MockProver::run(8, &circuit, public_inputs).unwrap().verify().is_ok()
+    }
+
+    #[test]
+    fn test_min_max() {
+        assert!(run(&[3, 7], 0, 100, [3, 7, 7, 3]));
+        assert!(run(&[7, 3], 0, 100, [3, 7, 7, 7]));
+        assert!(run(&[5, 5, 5], 0, 100, [5, 5, 5, 5]));
+
+        assert!(!run(&[3, 7], 0, 100, [7, 7, 7, 3]));
+        assert!(!run(&[3, 7], 0, 100, [3, 3, 7, 3]));
+    }
+
+    #[test]
+    fn test_max_of() {
+        assert!(run(&[10, 400, 65535, 2, 65534], 0, 65535, [10, 400, 65535, 10]));
+        assert!(run(&[1, 2, 3, 4, 5], 0, 65535, [1, 2, 5, 1]));
+
+        assert!(!run(&[1, 2, 3, 4, 5], 0, 65535, [1, 2, 4, 1]));
+    }
+
+    #[test]
+    fn test_clamp() {
+        for (x, clamped) in [(0, 10), (10, 10), (15, 15), (20, 20), (30, 20)] {
+            assert!(run(&[x, 0], 10, 20, [0, x, x, clamped]));
+            assert!(!run(&[x, 0], 10, 20, [0, x, x, clamped + 1]));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::lookup::RangeCheckTable;
+
+/// This gadget range-constrains an element to be n bits, for any n <= K, using the
+/// plain K-bit lookup table.
+///
+/// A single lookup only shows `value < 2^K`. We additionally look up the shifted
+/// value `value * 2^{K-n}`: for `value < 2^K` this product is at most `2^{2K-n}`, so
+/// it does not wrap around the modulus, and it is below `2^K` iff `value < 2^n`.
+///
+/// ```text
+///  |   value           | shift     | q_lookup | q_short |
+///  ------------------------------------------------------
+///  |   value           | 2^{K-n}   |    1     |    1    |
+///  | value * 2^{K-n}   |           |    1     |    0    |
+/// ```
+#[derive(Clone, Debug)]
+pub struct ShortRangeCheckConfig<F: PrimeField> {
+    value: Column<Advice>,
+    shift: Column<Fixed>,
+    lookup_selector: Selector,
+    short_selector: Selector,
+    lookup_table: RangeCheckTable<F>,
+    lookup_bits: usize,
+}
+
+impl<F: PrimeField> ShortRangeCheckConfig<F> {
+    pub fn lookup_bits(&self) -> usize {
+        self.lookup_bits
+    }
+
+    pub fn lookup_table(&self) -> &RangeCheckTable<F> {
+        &self.lookup_table
+    }
+}
+
+pub struct ShortRangeCheckChip<F: PrimeField> {
+    config: ShortRangeCheckConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for ShortRangeCheckChip<F> {
+    type Config = ShortRangeCheckConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField> ShortRangeCheckChip<F> {
+    pub fn construct(config: ShortRangeCheckConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        lookup_table: RangeCheckTable<F>,
+        lookup_bits: usize,
+    ) -> ShortRangeCheckConfig<F> {
+        assert_eq!(lookup_table.lookup_range(), 1 << lookup_bits);
+
+        let lookup_selector = meta.complex_selector();
+        let short_selector = meta.selector();
+
+        let shift = meta.fixed_column();
+
+        meta.enable_equality(value);
+
+        meta.create_gate("short range check", |meta| {
+            let short_selector = meta.query_selector(short_selector);
+            let value_cur = meta.query_advice(value, Rotation::cur());
+            let shifted = meta.query_advice(value, Rotation::next());
+            let shift = meta.query_fixed(shift);
+
+            Constraints::with_selector(short_selector, [("shifted value", value_cur * shift - shifted)])
+        });
+
+        meta.lookup(|meta| {
+            let lookup_selector = meta.query_selector(lookup_selector);
+            let value = meta.query_advice(value, Rotation::cur());
+
+            vec![(lookup_selector * value, lookup_table.value)]
+        });
+
+        ShortRangeCheckConfig {
+            value,
+            shift,
+            lookup_selector,
+            short_selector,
+            lookup_table,
+            lookup_bits,
+        }
+    }
+
+    /// Constrains `value < 2^num_bits`, for `num_bits <= K`.
+    pub fn short_range_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        let config = self.config();
+
+        assert!(num_bits <= config.lookup_bits);
+
+        let shift = F::from(1 << (config.lookup_bits - num_bits));
+
+        layouter.assign_region(
+            || "short range check",
+            |mut region| {
+                config.lookup_selector.enable(&mut region, 0)?;
+                config.lookup_selector.enable(&mut region, 1)?;
+                config.short_selector.enable(&mut region, 0)?;
+
+                value.copy_advice(|| "value", &mut region, config.value, 0)?;
+                region.assign_fixed(|| "shift", config.shift, 0, || Value::known(shift))?;
+                region.assign_advice(
+                    || "shifted value",
+                    config.value,
+                    1,
+                    || value.value().map(|v| *v * shift),
+                )?;
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::Circuit;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct ShortRangeCheckCircuit<F: PrimeField, const LOOKUP_BITS: usize> {
+        value: Value<F>,
+        num_bits: usize,
+    }
+
+    impl<F: PrimeField, const LOOKUP_BITS: usize> Circuit<F> for ShortRangeCheckCircuit<F, LOOKUP_BITS> {
+        type Config = ShortRangeCheckConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                num_bits: self.num_bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << LOOKUP_BITS);
+
+            ShortRangeCheckChip::configure(meta, value, lookup_table, LOOKUP_BITS)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            config.lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let value = layouter.assign_region(|| "assign value", |mut region| {
+                region.assign_advice(|| "value", config.value, 0, || self.value)
+            })?;
+
+            let chip = ShortRangeCheckChip::construct(config);
+
+            chip.short_range_check(layouter.namespace(|| "short range check"), value, self.num_bits)
+        }
+    }
+
+    #[test]
+    fn test_short_range_check() {
+        for num_bits in 0..=4 {
+            for value in 0..(1 << num_bits) {
+                let circuit = ShortRangeCheckCircuit::<Fp, 4> {
+                    value: Value::known(Fp::from(value)),
+                    num_bits,
+                };
+
+                let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+
+                prover.assert_satisfied();
+            }
+
+            let circuit = ShortRangeCheckCircuit::<Fp, 4> {
+                value: Value::known(Fp::from(1 << num_bits)),
+                num_bits,
+            };
+
+            let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+    }
+}
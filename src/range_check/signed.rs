@@ -0,0 +1,250 @@
+use std::marker::PhantomData;
+
+use ff::PrimeFieldBits;
+use halo2_proofs::circuit::{AssignedCell, Chip, Layouter, Value};
+use halo2_proofs::pasta::group::ff::PrimeField;
+use halo2_proofs::plonk::{Advice, Column, Constraints, ConstraintSystem, Error, Expression, Fixed, Selector};
+use halo2_proofs::poly::Rotation;
+
+use crate::range_check::decompose::{DecomposeChip, DecomposeConfig};
+
+/// This gadget range-constrains a field element to the signed interval
+/// [-2^{n-1}, 2^{n-1}), where negative numbers are represented as `p - |value|`.
+///
+/// The value is offset into the unsigned range [0, 2^n) and split at bit n - 1:
+///
+/// ```text
+///  value + 2^{n-1} = low + 2^{n-1} * (1 - sign)
+/// ```
+///
+/// with `low` range-checked to n - 1 bits and `sign` boolean, so `sign = 1` exactly for
+/// negative values. The absolute value is then `abs = value * (1 - 2 * sign)`.
+///
+/// ```text
+///  | value | low | sign | abs |   half    | q_signed |
+///  ---------------------------------------------------
+///  |   v   |  l  |  s   | |v| |  2^{n-1}  |    1     |
+/// ```
+#[derive(Clone, Debug)]
+pub struct SignedConfig<F: PrimeField> {
+    advice: [Column<Advice>; 4],
+    half: Column<Fixed>,
+    selector: Selector,
+    decompose: DecomposeConfig<F>,
+}
+
+/// The sign and absolute value of a signed integer, as returned by [`SignedChip::sign_and_abs`].
+#[derive(Clone, Debug)]
+pub struct SignAndAbs<F: PrimeField> {
+    /// 1 for negative values, 0 otherwise.
+    pub sign: AssignedCell<F, F>,
+    pub abs: AssignedCell<F, F>,
+}
+
+pub struct SignedChip<F: PrimeField> {
+    config: SignedConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Chip<F> for SignedChip<F> {
+    type Config = SignedConfig<F>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: PrimeField + PrimeFieldBits> SignedChip<F> {
+    pub fn construct(config: SignedConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        decompose: DecomposeConfig<F>,
+    ) -> SignedConfig<F> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        let half = meta.fixed_column();
+        let selector = meta.selector();
+
+        meta.create_gate("signed", |meta| {
+            let selector = meta.query_selector(selector);
+            let value = meta.query_advice(advice[0], Rotation::cur());
+            let low = meta.query_advice(advice[1], Rotation::cur());
+            let sign = meta.query_advice(advice[2], Rotation::cur());
+            let abs = meta.query_advice(advice[3], Rotation::cur());
+            let half = meta.query_fixed(half);
+
+            let one = Expression::Constant(F::ONE);
+            let two = Expression::Constant(F::from(2));
+
+            Constraints::with_selector(selector, [
+                ("offset value", value.clone() + half.clone() - low - half * (one.clone() - sign.clone())),
+                ("sign is boolean", sign.clone() * (one.clone() - sign.clone())),
+                ("absolute value", value * (one - two * sign) - abs),
+            ])
+        });
+
+        SignedConfig {
+            advice,
+            half,
+            selector,
+            decompose,
+        }
+    }
+
+    /// Constrains `-2^{num_bits - 1} <= value < 2^{num_bits - 1}`, returning the sign bit
+    /// (1 for negative values) and the absolute value.
+    pub fn sign_and_abs(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<SignAndAbs<F>, Error> {
+        assert!(num_bits >= 2 && num_bits < F::NUM_BITS as usize);
+
+        let config = self.config();
+
+        let half = F::from(2).pow_vartime([num_bits as u64 - 1]);
+
+        let (low, sign, abs) = layouter.assign_region(
+            || "signed",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+
+                value.copy_advice(|| "value", &mut region, config.advice[0], 0)?;
+                region.assign_fixed(|| "2^{n-1}", config.half, 0, || Value::known(half))?;
+
+                // Negative values are those whose offset lands below 2^{n-1}.
+                let offset = value.value().map(|v| *v + half);
+                let sign = offset.map(|offset| {
+                    let negative = offset.to_le_bits().iter().by_vals().skip(num_bits - 1).all(|bit| !bit);
+
+                    if negative { F::ONE } else { F::ZERO }
+                });
+                let low = offset.zip(sign).map(|(offset, sign)| offset - half * (F::ONE - sign));
+                let abs = value.value().copied().zip(sign).map(|(v, sign)| if sign == F::ONE { -v } else { v });
+
+                let low = region.assign_advice(|| "low", config.advice[1], 0, || low)?;
+                let sign = region.assign_advice(|| "sign", config.advice[2], 0, || sign)?;
+                let abs = region.assign_advice(|| "abs", config.advice[3], 0, || abs)?;
+
+                Ok((low, sign, abs))
+            },
+        )?;
+
+        let decompose_chip = DecomposeChip::construct(config.decompose.clone());
+
+        decompose_chip.assign(layouter.namespace(|| "range check low"), low, num_bits - 1, true)?;
+
+        Ok(SignAndAbs { sign, abs })
+    }
+
+    /// Constrains `-2^{num_bits - 1} <= value < 2^{num_bits - 1}`.
+    pub fn range_check(
+        &self,
+        layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        self.sign_and_abs(layouter, value, num_bits).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::circuit::SimpleFloorPlanner;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::Fp;
+    use halo2_proofs::plonk::{Circuit, Instance};
+
+    use crate::range_check::lookup_with_bits::RangeCheckTable;
+
+    use super::*;
+
+    struct SignedCircuit<F: PrimeField> {
+        value: Value<F>,
+        num_bits: usize,
+    }
+
+    impl<F: PrimeField + PrimeFieldBits> Circuit<F> for SignedCircuit<F> {
+        type Config = (SignedConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                num_bits: self.num_bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let c_i_bits = meta.advice_column();
+            let instance = meta.instance_column();
+
+            meta.enable_equality(instance);
+
+            let lookup_table = RangeCheckTable::configure(meta, 1 << 4);
+            let decompose = DecomposeChip::configure(meta, advice[1], advice[2], c_i_bits, lookup_table, 4);
+
+            (SignedChip::configure(meta, advice, decompose), instance)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let (config, instance) = config;
+
+            config.decompose.lookup_table().load(layouter.namespace(|| "lookup table"))?;
+
+            let value = layouter.assign_region(|| "assign value", |mut region| {
+                region.assign_advice(|| "value", config.advice[0], 0, || self.value)
+            })?;
+
+            let chip = SignedChip::construct(config);
+
+            let signed = chip.sign_and_abs(layouter.namespace(|| "signed"), value, self.num_bits)?;
+
+            layouter.constrain_instance(signed.sign.cell(), instance, 0)?;
+            layouter.constrain_instance(signed.abs.cell(), instance, 1)
+        }
+    }
+
+    fn field(value: i64) -> Fp {
+        if value < 0 { -Fp::from(value.unsigned_abs()) } else { Fp::from(value as u64) }
+    }
+
+    #[test]
+    fn test_signed_range_check() {
+        for value in [-128, -127, -5, -1, 0, 1, 5, 127] {
+            let circuit = SignedCircuit { value: Value::known(field(value)), num_bits: 8 };
+
+            let public_inputs = vec![vec![Fp::from((value < 0) as u64), Fp::from(value.unsigned_abs())]];
+
+            let prover = MockProver::run(5, &circuit, public_inputs).unwrap();
+
+            prover.assert_satisfied();
+        }
+
+        for value in [-129, 128, -1 << 40, 1 << 40] {
+            let circuit = SignedCircuit { value: Value::known(field(value)), num_bits: 8 };
+
+            let public_inputs = vec![vec![Fp::from((value < 0) as u64), Fp::from(value.unsigned_abs())]];
+
+            let prover = MockProver::run(5, &circuit, public_inputs).unwrap();
+
+            assert!(prover.verify().is_err());
+        }
+    }
+}